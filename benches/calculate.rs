@@ -0,0 +1,71 @@
+use bevy::{
+    app::App,
+    asset::{Asset, AssetId, Assets, Handle},
+    color::Srgba,
+    pbr::StandardMaterial,
+    prelude::{BuildChildren, Component, Sprite},
+    reflect::TypePath,
+    sprite::ColorMaterial,
+    time::{Time, Virtual},
+};
+use bevy_mod_opacity::{Opacity, OpacityExtension, OpacityPlugin};
+use criterion::{criterion_group, criterion_main, Criterion};
+
+#[derive(Debug, Clone, TypePath, Asset, Opacity)]
+#[opacity(asset)]
+pub struct Tint {
+    #[opacity]
+    pub color: Srgba,
+}
+
+#[derive(Debug, Component)]
+pub struct TintHandle(pub Handle<Tint>);
+
+impl From<&TintHandle> for AssetId<Tint> {
+    fn from(value: &TintHandle) -> Self {
+        value.0.id()
+    }
+}
+
+fn app() -> App {
+    let mut app = App::new();
+    app.init_resource::<Time<Virtual>>()
+        .init_resource::<Assets<ColorMaterial>>()
+        .init_resource::<Assets<StandardMaterial>>()
+        .add_plugins(OpacityPlugin);
+    app
+}
+
+fn calculate(c: &mut Criterion) {
+    let mut app = app();
+    for _ in 0..100 {
+        let root = app.world_mut().spawn(Opacity(0.5)).id();
+        for _ in 0..100 {
+            app.world_mut()
+                .spawn((Opacity(0.5), Sprite::default()))
+                .set_parent(root);
+        }
+    }
+    app.update();
+    c.bench_function("calculate_10000", |b| b.iter(|| app.update()));
+}
+
+fn shared_asset(c: &mut Criterion) {
+    let mut app = app();
+    app.init_resource::<Assets<Tint>>()
+        .register_opacity_asset::<Tint, TintHandle>();
+    let handle = app.world_mut().resource_mut::<Assets<Tint>>().add(Tint {
+        color: Srgba::WHITE,
+    });
+    let root = app.world_mut().spawn(Opacity(0.5)).id();
+    for _ in 0..1000 {
+        app.world_mut()
+            .spawn(TintHandle(handle.clone()))
+            .set_parent(root);
+    }
+    app.update();
+    c.bench_function("shared_asset_1000", |b| b.iter(|| app.update()));
+}
+
+criterion_group!(benches, calculate, shared_asset);
+criterion_main!(benches);
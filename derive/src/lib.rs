@@ -1,8 +1,11 @@
 use proc_macro::TokenStream;
-use proc_macro2::{Literal, TokenTree};
+use proc_macro2::{Ident, Literal, TokenStream as TokenStream2, TokenTree};
 use proc_macro_error::{abort, proc_macro_error};
 use quote::quote;
-use syn::{parse_macro_input, spanned::Spanned, Data, DeriveInput, Type};
+use syn::{
+    parse_macro_input, parse_quote, spanned::Spanned, Attribute, Data, DeriveInput, Meta, Type,
+    WherePredicate,
+};
 
 /// Declare a `Component` or `Asset` as affected by opacity.
 ///
@@ -13,17 +16,29 @@ use syn::{parse_macro_input, spanned::Spanned, Data, DeriveInput, Type};
 /// * `#[opacity]`
 ///
 ///   Makes `bevy_mod_opacity` set its value as alpha,
-///   valid on `f32` or bevy's color types.
+///   valid on `f32`, bevy's color types or an `Option` of them.
+///   Fields with a generic type are bound by `OpacityAlpha`.
+///
+/// * `#[opacity(nested)]`
+///
+///   Forwards opacity to a field whose type also derives `Opacity`.
+///   Fields with a generic type are bound by `OpacityNested`.
 ///
 /// # Type Attributes
 ///
+/// * `#[opacity(nested)]`
+///
+///   Only implement `OpacityNested`, for plain data used in `#[opacity(nested)]` fields.
+///   `OpacityNested` is implemented by all derives.
+///
 /// * `#[opacity(asset)]`
 ///
-///   Register as an asset.
+///   Register as an asset, this does not add the material to the `App`,
+///   use `register_opacity_material2d`, `register_opacity_material3d` or `impl_opacity_material!`.
 ///   
 /// *  `#[opacity(extends = StandardMaterial)]`
 ///
-///   Registers `ExtendedMaterial<Base, Self>` where `Base` is also `OpacityAsset`.
+///   Registers `ExtendedMaterial<Base, Self>` where `Base` is `OpacityAsset` or `StandardMaterial`.
 ///
 /// *  `#[opacity(masks = StandardMaterial)]`
 ///
@@ -34,10 +49,17 @@ pub fn opacity(tokens: TokenStream) -> TokenStream {
     let input = parse_macro_input!(tokens as DeriveInput);
 
     let mut asset = false;
+    let mut nested = false;
     let mut extends = Vec::new();
     let mut masks = Vec::new();
     let mut fields = Vec::new();
+    let mut nested_fields = Vec::new();
     let name = input.ident;
+    let params: Vec<Ident> = input
+        .generics
+        .type_params()
+        .map(|x| x.ident.clone())
+        .collect();
 
     let Data::Struct(s) = input.data else {
         abort!(name.span(), "Only supports struct.")
@@ -47,7 +69,12 @@ pub fn opacity(tokens: TokenStream) -> TokenStream {
             for field in fields_named.named {
                 for attribute in field.attrs {
                     if attribute.path().is_ident("opacity") {
-                        fields.push(TokenTree::Ident(field.ident.clone().unwrap()));
+                        let ident = TokenTree::Ident(field.ident.clone().unwrap());
+                        if is_nested(&attribute) {
+                            nested_fields.push((ident, field.ty.clone()));
+                        } else {
+                            fields.push((ident, field.ty.clone()));
+                        }
                     }
                 }
             }
@@ -56,7 +83,12 @@ pub fn opacity(tokens: TokenStream) -> TokenStream {
             for (index, field) in fields_unnamed.unnamed.into_iter().enumerate() {
                 for attribute in field.attrs {
                     if attribute.path().is_ident("opacity") {
-                        fields.push(TokenTree::Literal(Literal::usize_unsuffixed(index)));
+                        let index = TokenTree::Literal(Literal::usize_unsuffixed(index));
+                        if is_nested(&attribute) {
+                            nested_fields.push((index, field.ty.clone()));
+                        } else {
+                            fields.push((index, field.ty.clone()));
+                        }
                     }
                 }
             }
@@ -73,12 +105,17 @@ pub fn opacity(tokens: TokenStream) -> TokenStream {
             .parse_nested_meta(|meta| {
                 if meta.path.is_ident("asset") {
                     asset = true;
+                } else if meta.path.is_ident("nested") {
+                    nested = true;
                 } else if meta.path.is_ident("extends") {
                     extends.push(meta.value()?.parse::<Type>()?);
                 } else if meta.path.is_ident("masks") {
                     masks.push(meta.value()?.parse::<Type>()?);
                 } else {
-                    abort!(meta.path.span(), "Expected 'asset', 'extends' or 'masks'.");
+                    abort!(
+                        meta.path.span(),
+                        "Expected 'asset', 'nested', 'extends' or 'masks'."
+                    );
                 }
                 Ok(())
             })
@@ -88,18 +125,59 @@ pub fn opacity(tokens: TokenStream) -> TokenStream {
         }
     }
     let crate0 = quote! {::bevy_mod_opacity};
-    if asset || !extends.is_empty() || !masks.is_empty() {
-        let mut result = quote! {};
-
+    let mut bounds: Vec<WherePredicate> = Vec::new();
+    let mut setters: Vec<TokenStream2> = Vec::new();
+    for (field, ty) in &fields {
+        if mentions(ty, &params) {
+            bounds.push(parse_quote!(#ty: #crate0::OpacityAlpha));
+            setters.push(quote!(#crate0::OpacityAlpha::set_alpha(&mut self.#field, opacity)));
+        } else {
+            setters.push(quote!(#crate0::set_alpha(&mut self.#field, opacity)));
+        }
+    }
+    for (field, ty) in &nested_fields {
+        if mentions(ty, &params) {
+            bounds.push(parse_quote!(#ty: #crate0::OpacityNested));
+        }
+        setters.push(quote!(#crate0::OpacityNested::apply_opacity(&mut self.#field, opacity)));
+    }
+    let mut generics = input.generics.clone();
+    generics.make_where_clause().predicates.extend(bounds);
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+    // Generic types only implement `Component` or `Asset` under their own bounds.
+    let with_self = |bound: TokenStream2| {
+        let mut generics = generics.clone();
+        if !params.is_empty() {
+            generics
+                .make_where_clause()
+                .predicates
+                .push(parse_quote!(#name #ty_generics: #bound));
+        }
+        generics.where_clause.map(|x| quote!(#x))
+    };
+    let component_where = with_self(quote!(#crate0::Component));
+    let asset_where = with_self(quote!(#crate0::Asset));
+    let mut result = quote! {
+        const _: () =  {
+            impl #impl_generics #crate0::OpacityNested for #name #ty_generics #where_clause {
+                fn apply_opacity(&mut self, opacity: f32) -> bool {
+                    false #(| #setters)*
+                }
+            }
+        };
+    };
+    if nested {
+        result.into()
+    } else if asset || !extends.is_empty() || !masks.is_empty() {
         if asset {
             result.extend(quote! {
                 const _: () =  {
-                    impl #crate0::OpacityAsset for #name {
+                    impl #impl_generics #crate0::OpacityAsset for #name #ty_generics #asset_where {
                         fn apply_opacity(
                             &mut self,
                             opacity: f32,
                         ) {
-                            #(#crate0::set_alpha(&mut self.#fields, opacity);)*
+                            #crate0::OpacityNested::apply_opacity(self, opacity);
                         }
                     }
                 };
@@ -109,10 +187,16 @@ pub fn opacity(tokens: TokenStream) -> TokenStream {
         for ty in extends {
             result.extend(quote! {
                 const _: () =  {
-                    impl #crate0::OpacityMaterialExtension<#ty> for #name {
-                        fn apply_opacity(a: &mut #ty, b: &mut Self, opacity: f32) {
-                            #crate0::OpacityAsset::apply_opacity(a, opacity);
-                            #(#crate0::set_alpha(&mut b.#fields, opacity);)*
+                    impl #impl_generics #crate0::OpacityMaterialExtension<#ty>
+                        for #name #ty_generics #where_clause
+                    {
+                        fn base_alpha(a: &#ty) -> Option<f32> {
+                            #crate0::OpacityBaseMaterial::base_alpha(a)
+                        }
+
+                        fn apply_opacity(a: &mut #ty, base: Option<f32>, b: &mut Self, opacity: f32) {
+                            #crate0::OpacityBaseMaterial::apply_opacity(a, base, opacity);
+                            #crate0::OpacityNested::apply_opacity(b, opacity);
                         }
                     }
                 };
@@ -121,9 +205,11 @@ pub fn opacity(tokens: TokenStream) -> TokenStream {
         for ty in masks {
             result.extend(quote! {
                 const _: () =  {
-                    impl #crate0::OpacityMaterialExtension<#ty> for #name {
-                        fn apply_opacity(a: &mut #ty, b: &mut Self, opacity: f32) {
-                            #(#crate0::set_alpha(&mut b.#fields, opacity);)*
+                    impl #impl_generics #crate0::OpacityMaterialExtension<#ty>
+                        for #name #ty_generics #where_clause
+                    {
+                        fn apply_opacity(_: &mut #ty, _: Option<f32>, b: &mut Self, opacity: f32) {
+                            #crate0::OpacityNested::apply_opacity(b, opacity);
                         }
                     }
                 };
@@ -131,9 +217,9 @@ pub fn opacity(tokens: TokenStream) -> TokenStream {
         }
         result.into()
     } else {
-        quote! {
+        result.extend(quote! {
             const _: () =  {
-                impl #crate0::OpacityQuery for &mut #name {
+                impl #impl_generics #crate0::OpacityQuery for &mut #name #ty_generics #component_where {
                     type Cx = ();
 
                     fn apply_opacity(
@@ -141,11 +227,49 @@ pub fn opacity(tokens: TokenStream) -> TokenStream {
                         _: &mut (),
                         opacity: f32,
                     ) {
-                        #(#crate0::set_alpha(&mut this.#fields, opacity);)*
+                        // Only mark as changed if a field was actually written.
+                        let item = #crate0::DetectChangesMut::bypass_change_detection(this);
+                        if #crate0::OpacityNested::apply_opacity(item, opacity) {
+                            #crate0::DetectChangesMut::set_changed(this);
+                        }
                     }
                 }
             };
+        });
+        result.into()
+    }
+}
+
+/// Returns `true` if a type refers to any of the generic parameters.
+fn mentions(ty: &Type, params: &[Ident]) -> bool {
+    fn walk(tokens: TokenStream2, params: &[Ident]) -> bool {
+        tokens.into_iter().any(|token| match token {
+            TokenTree::Ident(ident) => params.contains(&ident),
+            TokenTree::Group(group) => walk(group.stream(), params),
+            _ => false,
+        })
+    }
+    walk(quote!(#ty), params)
+}
+
+/// Returns `true` for `#[opacity(nested)]`.
+fn is_nested(attribute: &Attribute) -> bool {
+    let mut nested = false;
+    if let Meta::List(_) = attribute.meta {
+        #[allow(clippy::blocks_in_conditions)]
+        if attribute
+            .parse_nested_meta(|meta| {
+                if meta.path.is_ident("nested") {
+                    nested = true;
+                    Ok(())
+                } else {
+                    abort!(meta.path.span(), "Expected 'nested'.");
+                }
+            })
+            .is_err()
+        {
+            abort!(attribute.meta.span(), "Expected 'nested'.")
         }
-        .into()
     }
+    nested
 }
@@ -0,0 +1,68 @@
+use bevy::{
+    app::{App, Startup, Update},
+    asset::AssetServer,
+    prelude::{Camera2d, Commands, Entity, Res, ResMut, Resource, Sprite},
+    tasks::{block_on, futures_lite::future, AsyncComputeTaskPool, Task},
+    DefaultPlugins,
+};
+use bevy_mod_opacity::{OpacityEntityCommands, OpacityPlugin};
+
+/// A step of the cutscene.
+pub enum Step {
+    Show(&'static str),
+    Hide,
+}
+
+const SCRIPT: [Step; 5] = [
+    Step::Show("bevy.png"),
+    Step::Hide,
+    Step::Show("ferris.png"),
+    Step::Hide,
+    Step::Show("heart.png"),
+];
+
+/// Plays [`SCRIPT`], each step waits for the previous fade to complete.
+#[derive(Resource, Default)]
+pub struct Cutscene {
+    step: usize,
+    shown: Option<Entity>,
+    task: Option<Task<()>>,
+}
+
+pub fn main() {
+    App::new()
+        .add_plugins(DefaultPlugins)
+        .add_plugins(OpacityPlugin)
+        .init_resource::<Cutscene>()
+        .add_systems(Startup, |mut commands: Commands| {
+            commands.spawn(Camera2d);
+        })
+        .add_systems(Update, cutscene)
+        .run();
+}
+
+pub fn cutscene(mut commands: Commands, assets: Res<AssetServer>, mut cutscene: ResMut<Cutscene>) {
+    if let Some(task) = &mut cutscene.task {
+        if block_on(future::poll_once(task)).is_none() {
+            return;
+        }
+        cutscene.task = None;
+    }
+    let token = match SCRIPT.get(cutscene.step) {
+        Some(Step::Show(image)) => {
+            let mut entity = commands.spawn(Sprite::from_image(assets.load(*image)));
+            cutscene.shown = Some(entity.id());
+            entity.fade_in(1.5)
+        }
+        Some(Step::Hide) => match cutscene.shown.take() {
+            Some(entity) => commands.entity(entity).fade_out(1.),
+            None => return,
+        },
+        None => return,
+    };
+    cutscene.step += 1;
+    cutscene.task = Some(AsyncComputeTaskPool::get().spawn(async move {
+        token.await;
+        println!("Fade completed.");
+    }));
+}
@@ -0,0 +1,32 @@
+use std::time::Duration;
+
+use bevy::{
+    app::{App, Startup},
+    asset::Assets,
+    audio::{AudioPlayer, Pitch, PlaybackSettings},
+    color::Color,
+    math::Vec2,
+    prelude::{Camera2d, Commands, ResMut, Sprite},
+    DefaultPlugins,
+};
+use bevy_mod_opacity::{FadeOut, OpacityExtension, OpacityPlugin};
+
+pub fn main() {
+    App::new()
+        .add_plugins(DefaultPlugins)
+        .add_plugins(OpacityPlugin)
+        .register_opacity_audio()
+        .add_systems(Startup, init)
+        .run();
+}
+
+/// A sprite that fades out together with its sound.
+pub fn init(mut commands: Commands, mut pitches: ResMut<Assets<Pitch>>) {
+    commands.spawn(Camera2d);
+    commands.spawn((
+        Sprite::from_color(Color::WHITE, Vec2::new(200., 200.)),
+        AudioPlayer(pitches.add(Pitch::new(440., Duration::from_secs(10)))),
+        PlaybackSettings::LOOP,
+        FadeOut::new(5.),
+    ));
+}
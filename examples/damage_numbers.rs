@@ -0,0 +1,59 @@
+use bevy::{
+    app::{App, Startup, Update},
+    color::Color,
+    math::Vec3,
+    prelude::{Camera2d, Commands, Component, Query, Res, Transform},
+    text::{Text2d, TextColor, TextFont},
+    time::{Time, Timer, TimerMode},
+    DefaultPlugins,
+};
+use bevy_mod_opacity::{FadeOut, OpacityPlugin};
+
+/// Upward velocity of a damage number.
+#[derive(Debug, Component)]
+pub struct Floating(f32);
+
+/// Spawns a damage number on every tick.
+#[derive(Debug, Component)]
+pub struct Spawner(Timer, u32);
+
+pub fn main() {
+    App::new()
+        .add_plugins(DefaultPlugins)
+        .add_plugins(OpacityPlugin)
+        .add_systems(Startup, init)
+        .add_systems(Update, (spawn_numbers, float))
+        .run();
+}
+
+pub fn init(mut commands: Commands) {
+    commands.spawn(Camera2d);
+    commands.spawn(Spawner(Timer::from_seconds(0.3, TimerMode::Repeating), 0));
+}
+
+/// Spawn a number that floats up and fades out, it is despawned once invisible.
+pub fn spawn_numbers(mut commands: Commands, time: Res<Time>, mut spawners: Query<&mut Spawner>) {
+    for mut spawner in &mut spawners {
+        if !spawner.0.tick(time.delta()).just_finished() {
+            continue;
+        }
+        spawner.1 += 1;
+        let n = spawner.1;
+        let x = (n * 97 % 400) as f32 - 200.;
+        let damage = n * 37 % 100 + 1;
+        commands.spawn((
+            Text2d::new(damage.to_string()),
+            TextFont::from_font_size(32. + damage as f32 / 4.),
+            TextColor(Color::srgb(1., 0.3, 0.2)),
+            Transform::from_xyz(x, 0., 0.),
+            Floating(60.),
+            FadeOut::new(1.2).with_curve(|x| x * x),
+        ));
+    }
+}
+
+pub fn float(time: Res<Time>, mut query: Query<(&mut Transform, &Floating)>) {
+    for (mut transform, floating) in &mut query {
+        transform.translation += Vec3::Y * floating.0 * time.delta_secs();
+    }
+}
@@ -0,0 +1,66 @@
+use bevy::{
+    app::{App, Startup, Update},
+    color::Color,
+    math::{Vec2, Vec3},
+    prelude::{Camera2d, Commands, Component, Query, Res, Sprite, Transform, Visibility},
+    time::{Time, Timer, TimerMode},
+    DefaultPlugins,
+};
+use bevy_mod_opacity::{OpacityCommands, OpacityPlugin};
+
+/// Velocity of a particle.
+#[derive(Debug, Component)]
+pub struct Velocity(Vec2);
+
+/// Spawns a burst on every tick.
+#[derive(Debug, Component)]
+pub struct Spawner(Timer, u32);
+
+pub fn main() {
+    App::new()
+        .add_plugins(DefaultPlugins)
+        .add_plugins(OpacityPlugin)
+        .add_systems(Startup, init)
+        .add_systems(Update, (spawn_bursts, fly))
+        .run();
+}
+
+pub fn init(mut commands: Commands) {
+    commands.spawn(Camera2d);
+    commands.spawn(Spawner(Timer::from_seconds(0.5, TimerMode::Repeating), 0));
+}
+
+/// Spawn a ring of particles under one root, the whole group fades out and is despawned together.
+pub fn spawn_bursts(mut commands: Commands, time: Res<Time>, mut spawners: Query<&mut Spawner>) {
+    for mut spawner in &mut spawners {
+        if !spawner.0.tick(time.delta()).just_finished() {
+            continue;
+        }
+        spawner.1 += 1;
+        let n = spawner.1;
+        let center = Vec3::new(
+            (n * 131 % 600) as f32 - 300.,
+            (n * 71 % 300) as f32 - 150.,
+            0.,
+        );
+        let color = Color::hsl((n * 47 % 360) as f32, 0.8, 0.6);
+        commands.spawn_fading_group(
+            (Transform::from_translation(center), Visibility::default()),
+            (0..16).map(move |i| {
+                let direction = Vec2::from_angle(i as f32 / 16. * std::f32::consts::TAU);
+                (
+                    Sprite::from_color(color, Vec2::splat(6.)),
+                    Transform::default(),
+                    Velocity(direction * 120.),
+                )
+            }),
+            0.8,
+        );
+    }
+}
+
+pub fn fly(time: Res<Time>, mut query: Query<(&mut Transform, &Velocity)>) {
+    for (mut transform, velocity) in &mut query {
+        transform.translation += velocity.0.extend(0.) * time.delta_secs();
+    }
+}
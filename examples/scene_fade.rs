@@ -0,0 +1,74 @@
+use bevy::{
+    app::{App, Startup, Update},
+    asset::Assets,
+    color::{Color, Srgba},
+    math::{primitives::Cuboid, Vec3},
+    pbr::{DirectionalLight, MeshMaterial3d, PointLight, StandardMaterial},
+    prelude::{
+        AlphaMode, BuildChildren, Camera3d, ChildBuild, Commands, Mesh, Mesh3d, Res, ResMut,
+        Single, Transform, With,
+    },
+    time::Time,
+    DefaultPlugins,
+};
+use bevy_mod_opacity::{FadeLight, Opacity, OpacityPlugin, SceneFade};
+
+pub fn main() {
+    App::new()
+        .add_plugins(DefaultPlugins)
+        .add_plugins(OpacityPlugin)
+        .add_systems(Startup, init)
+        .add_systems(Update, pulse)
+        .run();
+}
+
+pub fn init(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+) {
+    commands.spawn((
+        Camera3d::default(),
+        Transform::from_xyz(0., 4., 8.).looking_at(Vec3::ZERO, Vec3::Y),
+    ));
+    // The sun is not part of the scene, but should dim with it.
+    let sun = commands
+        .spawn((
+            DirectionalLight {
+                shadows_enabled: true,
+                ..Default::default()
+            },
+            Transform::from_xyz(4., 8., 4.).looking_at(Vec3::ZERO, Vec3::Y),
+        ))
+        .id();
+    let cube = meshes.add(Cuboid::from_length(1.));
+    commands
+        .spawn((SceneFade::new([sun]), Transform::default()))
+        .with_children(|scene| {
+            for (x, color) in [(-2., Srgba::RED), (0., Srgba::GREEN), (2., Srgba::BLUE)] {
+                scene.spawn((
+                    Mesh3d(cube.clone()),
+                    MeshMaterial3d(materials.add(StandardMaterial {
+                        base_color: Color::Srgba(color),
+                        alpha_mode: AlphaMode::Blend,
+                        ..Default::default()
+                    })),
+                    Transform::from_xyz(x, 0.5, 0.),
+                ));
+            }
+            // Lights in the scene are dimmed by its opacity directly.
+            scene.spawn((
+                PointLight {
+                    color: Color::WHITE,
+                    ..Default::default()
+                },
+                FadeLight,
+                Transform::from_xyz(0., 2., 2.),
+            ));
+        });
+}
+
+/// Fade the scene in and out continuously.
+pub fn pulse(time: Res<Time>, mut scene: Single<&mut Opacity, With<SceneFade>>) {
+    scene.0 = time.elapsed_secs().cos() * 0.5 + 0.5;
+}
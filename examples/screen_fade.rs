@@ -0,0 +1,70 @@
+use bevy::{
+    app::{App, Startup, Update},
+    asset::AssetServer,
+    prelude::{
+        Camera2d, Commands, Component, DespawnRecursiveExt, Entity, Query, Res, ResMut, Resource,
+        Single, Sprite, With, Without,
+    },
+    time::Time,
+    DefaultPlugins,
+};
+use bevy_mod_opacity::{FadeIn, Opacity, OpacityPlugin, ScreenFade, ScreenFadeOverlay};
+
+#[derive(Debug, Component)]
+pub struct Scenery;
+
+/// Index of the current scene and time spent in it.
+#[derive(Debug, Resource, Default)]
+pub struct SceneIndex(usize, f32);
+
+const SCENES: [&str; 3] = ["bevy.png", "ferris.png", "heart.png"];
+
+pub fn main() {
+    App::new()
+        .add_plugins(DefaultPlugins)
+        .add_plugins(OpacityPlugin)
+        .init_resource::<SceneIndex>()
+        .add_systems(Startup, init)
+        .add_systems(Update, (fade_to_black, swap_scene))
+        .run();
+}
+
+pub fn init(mut commands: Commands, assets: Res<AssetServer>) {
+    commands.spawn((Camera2d, ScreenFade::from_black(1.)));
+    commands.spawn((Sprite::from_image(assets.load(SCENES[0])), Scenery));
+}
+
+/// Fade to black after showing a scene for 3 seconds.
+pub fn fade_to_black(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut index: ResMut<SceneIndex>,
+    camera: Single<Entity, With<Camera2d>>,
+) {
+    index.1 += time.delta_secs();
+    if index.1 > 3. {
+        index.1 = f32::NEG_INFINITY;
+        commands.entity(*camera).insert(ScreenFade::to_black(1.));
+    }
+}
+
+/// Swap the scene once the screen is fully black.
+pub fn swap_scene(
+    mut commands: Commands,
+    assets: Res<AssetServer>,
+    mut index: ResMut<SceneIndex>,
+    camera: Single<Entity, With<Camera2d>>,
+    overlay: Query<&Opacity, (With<ScreenFadeOverlay>, Without<FadeIn>)>,
+    scenery: Query<Entity, With<Scenery>>,
+) {
+    if index.1 >= 0. || !overlay.iter().any(|x| *x == Opacity::OPAQUE) {
+        return;
+    }
+    for entity in &scenery {
+        commands.entity(entity).despawn_recursive();
+    }
+    index.0 = (index.0 + 1) % SCENES.len();
+    index.1 = 0.;
+    commands.spawn((Sprite::from_image(assets.load(SCENES[index.0])), Scenery));
+    commands.entity(*camera).insert(ScreenFade::from_black(1.));
+}
@@ -0,0 +1,66 @@
+use bevy::{
+    app::{App, Startup, Update},
+    asset::AssetServer,
+    input::{mouse::MouseButton, ButtonInput},
+    prelude::{
+        in_state, AppExtStates, Camera2d, Commands, Component, Entity, IntoSystemConfigs, OnEnter,
+        Query, Res, Sprite, States, With, Without,
+    },
+    DefaultPlugins,
+};
+use bevy_mod_opacity::{FadeCurve, FadeIn, FadeOut, OpacityPlugin};
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Default, States)]
+pub enum Screen {
+    #[default]
+    Menu,
+    Game,
+}
+
+#[derive(Debug, Component)]
+pub struct MenuRoot;
+
+pub fn main() {
+    App::new()
+        .add_plugins(DefaultPlugins)
+        .add_plugins(OpacityPlugin)
+        .init_state::<Screen>()
+        .add_systems(Startup, init)
+        .add_systems(Update, leave_menu.run_if(in_state(Screen::Menu)))
+        .add_systems(OnEnter(Screen::Game), enter_game)
+        .run();
+}
+
+pub fn init(mut commands: Commands, assets: Res<AssetServer>) {
+    commands.spawn(Camera2d);
+    commands.spawn((
+        Sprite::from_image(assets.load("bevy.png")),
+        FadeIn::new(1.),
+        MenuRoot,
+    ));
+}
+
+/// Fade out the menu on click, the state changes once it is gone.
+pub fn leave_menu(
+    mut commands: Commands,
+    mouse: Res<ButtonInput<MouseButton>>,
+    menu: Query<Entity, (With<MenuRoot>, Without<FadeOut>)>,
+) {
+    if !mouse.just_pressed(MouseButton::Left) {
+        return;
+    }
+    for entity in &menu {
+        commands.entity(entity).insert(
+            FadeOut::new(1.)
+                .then_set_state(Screen::Game)
+                .with_fade_curve(FadeCurve::EaseIn),
+        );
+    }
+}
+
+pub fn enter_game(mut commands: Commands, assets: Res<AssetServer>) {
+    commands.spawn((
+        Sprite::from_image(assets.load("ferris.png")),
+        FadeIn::new(1.),
+    ));
+}
@@ -0,0 +1,85 @@
+use bevy::{
+    app::{App, Startup, Update},
+    asset::AssetServer,
+    color::Color,
+    input::{mouse::MouseButton, ButtonInput},
+    prelude::{
+        BuildChildren, Camera2d, ChildBuild, Commands, Component, Entity, ImageNode, Query, Res,
+        Text, With,
+    },
+    text::{TextColor, TextFont, TextSpan},
+    ui::{AlignItems, BackgroundColor, BorderRadius, Node, PositionType, UiRect, Val},
+    DefaultPlugins,
+};
+use bevy_mod_opacity::{FadeIn, FadeOut, OpacityPlugin, UiOpacity};
+
+#[derive(Debug, Component)]
+pub struct Tooltip;
+
+pub fn main() {
+    App::new()
+        .add_plugins(DefaultPlugins)
+        .add_plugins(OpacityPlugin)
+        .add_systems(Startup, init)
+        .add_systems(Update, toggle_tooltip)
+        .run();
+}
+
+pub fn init(mut commands: Commands) {
+    commands.spawn(Camera2d);
+}
+
+/// Click to show a tooltip, click again to fade it out.
+///
+/// The icon, text and background fade together as one unit.
+pub fn toggle_tooltip(
+    mut commands: Commands,
+    assets: Res<AssetServer>,
+    mouse: Res<ButtonInput<MouseButton>>,
+    tooltips: Query<Entity, With<Tooltip>>,
+) {
+    if !mouse.just_pressed(MouseButton::Left) {
+        return;
+    }
+    if let Some(tooltip) = tooltips.iter().next() {
+        commands
+            .entity(tooltip)
+            .remove::<(Tooltip, FadeIn)>()
+            .insert(FadeOut::new(0.5));
+        return;
+    }
+    commands
+        .spawn((
+            Node {
+                position_type: PositionType::Absolute,
+                left: Val::Px(40.),
+                top: Val::Px(40.),
+                padding: UiRect::all(Val::Px(8.)),
+                column_gap: Val::Px(8.),
+                align_items: AlignItems::Center,
+                ..Default::default()
+            },
+            BackgroundColor(Color::srgba(0.1, 0.1, 0.1, 0.9)),
+            BorderRadius::all(Val::Px(6.)),
+            UiOpacity::Background,
+            Tooltip,
+            FadeIn::new(0.5),
+        ))
+        .with_children(|build| {
+            build.spawn((
+                ImageNode::new(assets.load("heart.png")),
+                Node {
+                    width: Val::Px(32.),
+                    height: Val::Px(32.),
+                    ..Default::default()
+                },
+            ));
+            build
+                .spawn((Text::new("Heart "), TextFont::from_font_size(24.)))
+                .with_child((
+                    TextSpan::new("+1 health"),
+                    TextFont::from_font_size(24.),
+                    TextColor(Color::srgb(0.4, 1., 0.4)),
+                ));
+        });
+}
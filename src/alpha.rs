@@ -1,25 +1,87 @@
 //! Magic for using bevy's alpha and f32.
 
-use bevy::color::Alpha;
+use std::marker::PhantomData;
+
+use bevy::color::{
+    Alpha, Color, Hsla, Hsva, Hwba, Laba, Lcha, LinearRgba, Oklaba, Oklcha, Srgba, Xyza,
+};
+
 pub struct BevyAlphaMarker;
 pub struct F32Marker;
+pub struct OptionMarker<M>(PhantomData<M>);
 
 pub trait SetAlpha<M> {
-    fn set_alpha(&mut self, alpha: f32);
+    /// Set alpha, returns `true` if it was changed.
+    fn set_alpha(&mut self, alpha: f32) -> bool;
 }
 
 impl<T: Alpha> SetAlpha<BevyAlphaMarker> for T {
-    fn set_alpha(&mut self, alpha: f32) {
+    fn set_alpha(&mut self, alpha: f32) -> bool {
+        if self.alpha() == alpha {
+            return false;
+        }
         Alpha::set_alpha(self, alpha);
+        true
     }
 }
 
 impl SetAlpha<F32Marker> for f32 {
-    fn set_alpha(&mut self, alpha: f32) {
+    fn set_alpha(&mut self, alpha: f32) -> bool {
+        if *self == alpha {
+            return false;
+        }
         *self = alpha;
+        true
+    }
+}
+
+impl<T: SetAlpha<M>, M> SetAlpha<OptionMarker<M>> for Option<T> {
+    fn set_alpha(&mut self, alpha: f32) -> bool {
+        match self {
+            Some(item) => item.set_alpha(alpha),
+            None => false,
+        }
+    }
+}
+
+pub fn set_alpha<T: SetAlpha<A>, A>(item: &mut T, alpha: f32) -> bool {
+    item.set_alpha(alpha)
+}
+
+/// Types whose alpha can be set on generic `#[opacity]` fields of the [`Opacity`](crate::Opacity) derive macro.
+///
+/// Concrete fields accept any type implementing [`Alpha`], `f32` or an `Option` of them,
+/// the macro cannot pick between these for a type parameter, so generic fields are bound by this trait instead.
+/// Implemented for bevy's color types, `f32` and `Option` of them,
+/// implement this on your own [`Alpha`] types to use them as generic fields.
+pub trait OpacityAlpha {
+    /// Set alpha, returns `true` if it was changed.
+    fn set_alpha(&mut self, alpha: f32) -> bool;
+}
+
+macro_rules! impl_opacity_alpha {
+    ($($ty: ty),* $(,)?) => {
+        $(impl OpacityAlpha for $ty {
+            fn set_alpha(&mut self, alpha: f32) -> bool {
+                SetAlpha::set_alpha(self, alpha)
+            }
+        })*
+    };
+}
+
+impl_opacity_alpha!(Color, Srgba, LinearRgba, Hsla, Hsva, Hwba, Laba, Lcha, Oklaba, Oklcha, Xyza);
+
+impl OpacityAlpha for f32 {
+    fn set_alpha(&mut self, alpha: f32) -> bool {
+        SetAlpha::set_alpha(self, alpha)
     }
 }
 
-pub fn set_alpha<T: SetAlpha<A>, A>(item: &mut T, alpha: f32) {
-    item.set_alpha(alpha);
+impl<T: OpacityAlpha> OpacityAlpha for Option<T> {
+    fn set_alpha(&mut self, alpha: f32) -> bool {
+        match self {
+            Some(item) => item.set_alpha(alpha),
+            None => false,
+        }
+    }
 }
@@ -0,0 +1,17 @@
+use bevy::animation::animatable::{Animatable, BlendInput};
+
+use crate::Opacity;
+
+impl Animatable for Opacity {
+    fn interpolate(a: &Self, b: &Self, time: f32) -> Self {
+        Opacity(Opacity::lerp_unclamped(a.0, b.0, time))
+    }
+
+    fn blend(inputs: impl Iterator<Item = BlendInput<Self>>) -> Self {
+        Opacity(f32::blend(inputs.map(|input| BlendInput {
+            weight: input.weight,
+            value: input.value.0,
+            additive: input.additive,
+        })))
+    }
+}
@@ -0,0 +1,77 @@
+use bevy::{
+    app::{App, Plugin},
+    audio::{AudioSink, AudioSinkPlayback, SpatialAudioSink},
+    ecs::query::QueryData,
+    prelude::{Commands, Component, Entity, IntoSystemConfigs, Query, Res, Without},
+};
+
+use crate::{
+    add_opacity_systems, calculate_opacity, OpacityExtension, OpacityMap, OpacityQuery, OpacitySet,
+};
+
+/// Volume of an audio sink at full opacity, opacity is multiplied into this value.
+///
+/// Recorded automatically the first time an audio sink is affected by opacity,
+/// modify this to change the volume afterwards.
+#[derive(Debug, Clone, Copy, PartialEq, PartialOrd, Component)]
+pub struct BaseVolume(pub f32);
+
+/// [`QueryData`] for an audio sink that respects [`BaseVolume`].
+#[derive(QueryData)]
+pub struct AudioVolumeQuery<S: AudioSinkPlayback + Component> {
+    pub sink: &'static S,
+    pub base: Option<&'static BaseVolume>,
+}
+
+impl<S: AudioSinkPlayback + Component> OpacityQuery for AudioVolumeQuery<S> {
+    type Cx = ();
+
+    fn apply_opacity(this: &mut Self::Item<'_>, _: &mut (), opacity: f32) {
+        let volume = this.base.map(|x| x.0).unwrap_or(1.) * opacity;
+        if this.sink.volume() == volume {
+            return;
+        }
+        this.sink.set_volume(volume);
+    }
+}
+
+pub fn record_base_volume<S: AudioSinkPlayback + Component>(
+    mut commands: Commands,
+    map: Res<OpacityMap>,
+    query: Query<(Entity, &S), Without<BaseVolume>>,
+) {
+    for (entity, sink) in &query {
+        if map.contains(entity) {
+            commands.entity(entity).insert(BaseVolume(sink.volume()));
+        }
+    }
+}
+
+pub type AudioSinkVolumeQuery = AudioVolumeQuery<AudioSink>;
+pub type SpatialAudioSinkVolumeQuery = AudioVolumeQuery<SpatialAudioSink>;
+
+/// Added by [`OpacityExtension::register_opacity_audio`], registering again does nothing.
+#[derive(Debug)]
+pub(crate) struct AudioOpacityPlugin;
+
+impl Plugin for AudioOpacityPlugin {
+    fn build(&self, app: &mut App) {
+        if app.is_plugin_added::<Self>() {
+            return;
+        }
+        add_opacity_systems(app, || {
+            (
+                record_base_volume::<AudioSink>,
+                record_base_volume::<SpatialAudioSink>,
+            )
+                .after(calculate_opacity)
+                .in_set(OpacitySet::Calculate)
+        });
+        app.register_opacity::<AudioSinkVolumeQuery>();
+        app.register_opacity::<SpatialAudioSinkVolumeQuery>();
+    }
+
+    fn is_unique(&self) -> bool {
+        false
+    }
+}
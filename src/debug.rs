@@ -0,0 +1,128 @@
+use bevy::{
+    app::{App, Plugin, PostUpdate},
+    asset::{Asset, AssetId, AssetServer, Assets, Handle},
+    ecs::entity::{EntityHashMap, EntityHashSet},
+    log::{info, warn, warn_once},
+    pbr::{MeshMaterial3d, StandardMaterial},
+    prelude::{
+        Added, AlphaMode, Component, Entity, IntoSystemConfigs, Local, Parent, Query, Res, With,
+    },
+    utils::HashSet,
+};
+
+use crate::{Opacity, OpacityMap, OpacitySet};
+
+/// Number of consecutive frames an [`Opacity`] root can affect nothing before warning,
+/// this gives assets like scenes time to load.
+const GRACE_FRAMES: u32 = 120;
+
+/// Marks an entity matched by a registered [`OpacityQuery`](crate::OpacityQuery) while under opacity.
+///
+/// Inserted through commands so apply systems do not share mutable state and can run in parallel.
+#[derive(Debug, Clone, Copy, Default, Component)]
+pub struct OpacityAffected;
+
+/// Warn once if an entity with [`Opacity`] has no descendant affected by a registered query.
+///
+/// Only walks upward from newly affected entities, marking their ancestors with [`Opacity`] as supported.
+pub fn warn_unsupported(
+    mut frames: Local<EntityHashMap<u32>>,
+    mut supported: Local<EntityHashSet>,
+    mut visited: Local<Vec<Entity>>,
+    roots: Query<Entity, With<Opacity>>,
+    affected: Query<Entity, Added<OpacityAffected>>,
+    parents: Query<&Parent>,
+) {
+    for entity in &affected {
+        visited.clear();
+        let mut current = Some(entity);
+        while let Some(entity) = current {
+            if visited.contains(&entity) {
+                break;
+            }
+            visited.push(entity);
+            if roots.contains(entity) {
+                supported.insert(entity);
+            }
+            current = parents.get(entity).ok().map(|x| x.get());
+        }
+    }
+    frames.retain(|entity, _| roots.contains(*entity));
+    supported.retain(|entity| roots.contains(*entity));
+    for root in &roots {
+        if supported.contains(&root) {
+            continue;
+        }
+        let count = frames.entry(root).or_default();
+        *count = count.saturating_add(1);
+        if *count == GRACE_FRAMES {
+            warn!("Entity {root} has `Opacity` but no descendant is affected by a registered `OpacityQuery`.");
+        }
+    }
+}
+
+/// Warn once per asset if a [`StandardMaterial`] with [`AlphaMode::Opaque`] is faded,
+/// since alpha has no visual effect on opaque materials.
+pub fn warn_opaque_materials(
+    map: Res<OpacityMap>,
+    assets: Res<Assets<StandardMaterial>>,
+    server: Option<Res<AssetServer>>,
+    mut warned: Local<HashSet<AssetId<StandardMaterial>>>,
+    query: Query<(Entity, &MeshMaterial3d<StandardMaterial>)>,
+) {
+    for (entity, material) in &query {
+        if !map.get(entity).is_some_and(|x| x < 1.) || warned.contains(&material.id()) {
+            continue;
+        }
+        let Some(asset) = assets.get(material) else {
+            continue;
+        };
+        if asset.alpha_mode != AlphaMode::Opaque {
+            continue;
+        }
+        warned.insert(material.id());
+        let name = server
+            .as_ref()
+            .and_then(|x| x.get_path(material.id()))
+            .map(|x| x.to_string())
+            .unwrap_or_else(|| material.id().to_string());
+        warn!("`StandardMaterial` {name} on entity {entity} is faded but has `AlphaMode::Opaque`, set `AlphaMode::Blend` for opacity to have an effect.");
+    }
+}
+
+/// Warn once if a material under opacity has a weak handle to an asset that does not exist,
+/// since it will never fade.
+pub fn warn_weak_handle<A: Asset>(handle: &Handle<A>, assets: &Assets<A>) {
+    if handle.is_weak() && !assets.contains(handle) {
+        warn_once!(
+            "A weak handle to {} {} is faded but the asset does not exist, use strong handles for opacity to have an effect.",
+            A::short_type_path(),
+            handle.id()
+        );
+    }
+}
+
+/// Logs the computed opacity of every entity in [`OpacityMap`] when it changes,
+/// for diagnosing why something is not fading as expected.
+///
+/// Requires [`OpacityPlugin`](crate::OpacityPlugin).
+#[derive(Debug, Clone, Copy, Default)]
+pub struct OpacityDebugPlugin;
+
+impl Plugin for OpacityDebugPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(PostUpdate, log_opacity.after(OpacitySet::Calculate));
+    }
+}
+
+fn log_opacity(map: Res<OpacityMap>, mut logged: Local<EntityHashMap<f32>>) {
+    logged.retain(|entity, _| map.contains(*entity));
+    for (entity, data) in map.0.iter() {
+        if logged.insert(*entity, data.value) != Some(data.value) {
+            info!(
+                "Entity {entity} has opacity {} from root {}.",
+                data.value, data.root
+            );
+        }
+    }
+}
@@ -0,0 +1,60 @@
+use bevy::prelude::{Camera, Component, GlobalTransform, Query, Without};
+
+use crate::Opacity;
+
+/// Sets [`Opacity`] from the distance to the active camera with the lowest order,
+/// `1.0` within `near` and `0.0` beyond `far`, linear in between.
+///
+/// Opacity is left unchanged if there is no active camera.
+/// If [`Opacity`] is not present, insert at `1.0`.
+#[derive(Debug, Clone, Copy, PartialEq, Component)]
+#[require(Opacity)]
+pub struct DistanceFade {
+    pub near: f32,
+    pub far: f32,
+}
+
+impl DistanceFade {
+    pub fn new(near: f32, far: f32) -> Self {
+        DistanceFade { near, far }
+    }
+
+    /// Returns the opacity at `distance`.
+    ///
+    /// ```
+    /// # use bevy_mod_opacity::DistanceFade;
+    /// let fade = DistanceFade::new(10.0, 20.0);
+    /// assert_eq!(fade.sample(5.0), 1.0);
+    /// assert_eq!(fade.sample(15.0), 0.5);
+    /// assert_eq!(fade.sample(25.0), 0.0);
+    /// ```
+    pub fn sample(&self, distance: f32) -> f32 {
+        if distance <= self.near {
+            return 1.;
+        }
+        if distance >= self.far {
+            return 0.;
+        }
+        1. - (distance - self.near) / (self.far - self.near)
+    }
+}
+
+pub fn distance_fade(
+    cameras: Query<(&Camera, &GlobalTransform), Without<DistanceFade>>,
+    mut query: Query<(&DistanceFade, &GlobalTransform, &mut Opacity)>,
+) {
+    let Some((_, camera)) = cameras
+        .iter()
+        .filter(|(camera, _)| camera.is_active)
+        .min_by_key(|(camera, _)| camera.order)
+    else {
+        return;
+    };
+    let camera = camera.translation();
+    for (fade, transform, mut opacity) in &mut query {
+        let value = fade.sample(transform.translation().distance(camera));
+        if opacity.0 != value {
+            opacity.0 = value;
+        }
+    }
+}
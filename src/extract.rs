@@ -0,0 +1,48 @@
+use bevy::{prelude::Component, render::extract_component::ExtractComponent};
+
+use crate::OpacityQuery;
+
+/// Opt-in [`Component`] holding the computed opacity of an entity,
+/// for colors that are driven in the render world.
+///
+/// Opacity is calculated and applied in the main world during `PostUpdate`,
+/// before extraction, this crate never touches the render world itself.
+/// Insert this on an entity and either add `ExtractComponentPlugin::<RenderOpacity>`
+/// to copy it to the render world, or read it when extracting your own component:
+///
+/// ```
+/// # use bevy::prelude::*;
+/// # use bevy::render::extract_component::ExtractComponent;
+/// # use bevy_mod_opacity::RenderOpacity;
+/// #[derive(Clone, Component)]
+/// struct Glow(LinearRgba);
+///
+/// impl ExtractComponent for Glow {
+///     type QueryData = (&'static Glow, &'static RenderOpacity);
+///     type QueryFilter = ();
+///     type Out = Glow;
+///
+///     fn extract_component((glow, opacity): (&Glow, &RenderOpacity)) -> Option<Glow> {
+///         Some(Glow(glow.0 * opacity.0))
+///     }
+/// }
+/// ```
+#[derive(Debug, Clone, Copy, Component, PartialEq, PartialOrd, ExtractComponent)]
+pub struct RenderOpacity(pub f32);
+
+impl Default for RenderOpacity {
+    fn default() -> Self {
+        Self(1.0)
+    }
+}
+
+impl OpacityQuery for &mut RenderOpacity {
+    type Cx = ();
+
+    fn apply_opacity(this: &mut Self::Item<'_>, _: &mut (), opacity: f32) {
+        if this.0 == opacity {
+            return;
+        }
+        this.0 = opacity;
+    }
+}
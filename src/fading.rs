@@ -1,35 +1,271 @@
+#[cfg(feature = "state")]
+use bevy::state::state::{FreelyMutableState, NextState};
 use bevy::{
-    prelude::{Commands, Component, DespawnRecursiveExt, Entity, Query, Res},
-    time::{Time, Virtual},
+    ecs::query::{Has, QueryData},
+    math::curve::{Curve, EaseFunction, EasingCurve},
+    prelude::{
+        BuildChildren, Bundle, ChildBuild, Children, Commands, Component, DespawnRecursiveExt,
+        Entity, Event, EventWriter, Query, Res, Resource, With, World,
+    },
+    time::Time,
 };
 
-use crate::Opacity;
+use std::{
+    fmt::{self, Debug, Formatter},
+    sync::Arc,
+};
+
+use crate::{
+    apply_opacity_now, Opacity, OpacityDisabled, OpacityFloor, OpacityFollow, OpacityTrack,
+};
 
 /// When inserted, gradually increase opacity to `1.0` within the given time.
 ///
-/// If [`Opacity`] is not present, insert at `0.0`.
+/// A time of `0.0` or less, or `NaN`, completes the fade immediately.
+///
+/// If [`Opacity`] is not present, insert at `0.0`, otherwise start from the existing value,
+/// this makes inserting on a partially visible entity continue from where it is,
+/// see [`FadeIn::from_current`] to take the full time from there.
 ///
 /// This component is removed afterwards and opacity is
-/// guaranteed to be equal to `1.0` after this is removed.
+/// guaranteed to be equal to `1.0` after this is removed,
+/// unless [`FadeIn::despawning`] is used.
 #[derive(Debug, Clone, Copy, Component)]
 #[require(Opacity(||Opacity::INVISIBLE))]
 pub struct FadeIn {
     pub(crate) current: f32,
     pub(crate) time: f32,
-    pub(crate) curve: Option<fn(f32) -> f32>,
+    pub(crate) curve: Option<CurveFn>,
+    pub(crate) start: Option<f32>,
+    pub(crate) from_current: bool,
+    pub(crate) despawns: bool,
+}
+
+/// [`FadeIn`] from opacity `0.0`, for the fade in on spawn pattern.
+///
+/// Unlike [`FadeIn`] alone, this always starts from `0.0`,
+/// even if the entity already has [`Opacity`].
+///
+/// ```
+/// # use bevy::prelude::*;
+/// # use bevy_mod_opacity::{FadeInBundle, Opacity};
+/// # let mut world = World::new();
+/// let entity = world.spawn((Sprite::default(), FadeInBundle::new(2.0))).id();
+/// assert_eq!(world.get::<Opacity>(entity), Some(&Opacity::INVISIBLE));
+/// ```
+#[derive(Debug, Clone, Copy, Bundle)]
+pub struct FadeInBundle {
+    pub opacity: Opacity,
+    pub fade: FadeIn,
 }
 
-/// When inserted, gradually decrease opacity to `0.0` within the given time.
+impl FadeInBundle {
+    pub fn new(time: f32) -> Self {
+        FadeInBundle {
+            opacity: Opacity::INVISIBLE,
+            fade: FadeIn::new(time),
+        }
+    }
+}
+
+/// When inserted, gradually decrease opacity to `0.0`,
+/// or the value set by [`FadeOut::with_target`], within the given time.
+///
+/// A time of `0.0` or less, or `NaN`, completes the fade immediately.
+/// Opacity is never raised, if it is already at or below the target
+/// the fade completes without changing it.
 ///
 /// If [`Opacity`] is not present, insert at `1.0`.
 ///
-/// This entity and all its children will be removed afterwards.
+/// This entity and all its children will be removed afterwards,
+/// unless [`FadeOut::despawn_marked`] is used or [`FadeConfig::despawn_mode`] is changed.
+/// Entities with an [`OpacityFloor`] are kept, since they stay visible at the floor.
+///
+/// When recycling a pooled entity, a stale [`FadeOut`] must be removed
+/// and opacity reset, use [`reset_fade`] to do both.
 #[derive(Debug, Clone, Copy, Component)]
-#[require(Opacity(||Opacity::FULL))]
+#[require(Opacity(||Opacity::OPAQUE))]
 pub struct FadeOut {
     pub(crate) current: f32,
     pub(crate) time: f32,
-    pub(crate) curve: Option<fn(f32) -> f32>,
+    pub(crate) curve: Option<CurveFn>,
+    pub(crate) filter: Option<fn(&World, Entity) -> bool>,
+    pub(crate) start: Option<f32>,
+    pub(crate) target: f32,
+}
+
+/// Command run once a [`FadeOut`] on the same entity completes, before despawning.
+#[derive(Clone, Component)]
+pub(crate) struct Then(Arc<dyn Fn(&mut World) + Send + Sync>);
+
+impl Debug for Then {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.write_str("Then")
+    }
+}
+
+/// Preset curves for [`FadeIn::with_fade_curve`], [`FadeOut::with_fade_curve`]
+/// and [`OpacityTrack::with_fade_curve`].
+///
+/// Presets clamp their input to `0..=1`.
+#[derive(Debug, Clone, Copy, Default)]
+pub enum FadeCurve {
+    /// `x`
+    #[default]
+    Linear,
+    /// Quadratic, starts slow.
+    EaseIn,
+    /// Quadratic, ends slow.
+    EaseOut,
+    /// Quadratic, starts and ends slow.
+    EaseInOut,
+    /// `3x² - 2x³`
+    SmoothStep,
+    /// A custom curve, see [`FadeIn::with_curve`].
+    Custom(fn(f32) -> f32),
+    /// One of bevy's easing functions, see [`FadeIn::with_ease`].
+    Ease(EaseFunction),
+}
+
+impl From<EaseFunction> for FadeCurve {
+    fn from(value: EaseFunction) -> Self {
+        FadeCurve::Ease(value)
+    }
+}
+
+impl FadeCurve {
+    /// Map a value in `0..1` to a value in `0..1`.
+    pub fn sample(&self, x: f32) -> f32 {
+        let x = match self {
+            FadeCurve::Custom(_) => x,
+            _ => x.clamp(0., 1.),
+        };
+        match self {
+            FadeCurve::Linear => x,
+            FadeCurve::EaseIn => x * x,
+            FadeCurve::EaseOut => 1. - (1. - x) * (1. - x),
+            FadeCurve::EaseInOut if x < 0.5 => 2. * x * x,
+            FadeCurve::EaseInOut => 1. - 2. * (1. - x) * (1. - x),
+            FadeCurve::SmoothStep => x * x * (3. - 2. * x),
+            FadeCurve::Custom(curve) => curve(x),
+            FadeCurve::Ease(ease) => EasingCurve::new(0., 1., *ease).sample_clamped(x),
+        }
+    }
+}
+
+/// Curve of [`FadeIn`] or [`FadeOut`].
+#[derive(Debug, Clone, Copy)]
+pub(crate) enum CurveFn {
+    /// Takes progress in `0..1`.
+    Normalized(fn(f32) -> f32),
+    /// Takes progress in `0..1`.
+    Preset(FadeCurve),
+    /// Takes elapsed seconds and duration.
+    Secs(fn(f32, f32) -> f32),
+}
+
+impl CurveFn {
+    fn sample(self, elapsed: f32, duration: f32) -> f32 {
+        match self {
+            CurveFn::Normalized(curve) => curve(elapsed / duration),
+            CurveFn::Preset(curve) => curve.sample(elapsed / duration),
+            CurveFn::Secs(curve) => curve(elapsed, duration),
+        }
+    }
+}
+
+/// Global settings for [`FadeIn`] and [`FadeOut`].
+#[derive(Debug, Clone, Copy, Resource)]
+pub struct FadeConfig {
+    /// If `false`, completed fades never despawn entities,
+    /// [`FadeOut`] and [`FadeIn::despawning`] leave the entity in place and remove themselves.
+    ///
+    /// Useful for editors and previews, by default `true`.
+    pub despawn_on_fade_out: bool,
+    /// What is despawned when [`FadeOut`] or [`FadeIn::despawning`] completes,
+    /// by default [`DespawnMode::Recursive`].
+    pub despawn_mode: DespawnMode,
+}
+
+impl Default for FadeConfig {
+    fn default() -> Self {
+        FadeConfig {
+            despawn_on_fade_out: true,
+            despawn_mode: DespawnMode::Recursive,
+        }
+    }
+}
+
+/// What is despawned when a fade completes, see [`FadeConfig::despawn_mode`].
+///
+/// Does not affect [`FadeOut::despawn_marked`], which always despawns marked entities recursively.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum DespawnMode {
+    /// Despawn the entity and all its descendants.
+    #[default]
+    Recursive,
+    /// Despawn all descendants, the entity stays and the fade component is removed.
+    DescendantsOnly,
+    /// Despawn only the entity, its children are detached and stay.
+    SelfOnly,
+}
+
+impl DespawnMode {
+    fn despawn<C: Component>(self, commands: &mut Commands, entity: Entity) {
+        let mut entity = commands.entity(entity);
+        match self {
+            DespawnMode::Recursive => entity.despawn_recursive(),
+            DespawnMode::DescendantsOnly => {
+                entity.despawn_descendants().remove::<C>();
+            }
+            DespawnMode::SelfOnly => entity.clear_children().despawn(),
+        }
+    }
+}
+
+/// When inserted, blink between a low opacity and `1.0` a number of times.
+///
+/// Each blink stays low for the first half of the period and at `1.0` for the second half.
+///
+/// This component is removed afterwards and opacity is
+/// guaranteed to be equal to `1.0` after this is removed.
+#[derive(Debug, Clone, Copy, Component)]
+#[require(Opacity)]
+pub struct Blink {
+    pub(crate) remaining: u32,
+    pub(crate) period: f32,
+    pub(crate) current: f32,
+    pub(crate) low: f32,
+}
+
+/// When inserted, fade in from `0.0`, stay at `1.0` for a while, then fade out and despawn,
+/// for toast notifications.
+///
+/// Sends [`FadeCompleted::Out`] and despawns like [`FadeOut`] at the end.
+///
+/// ```
+/// # use bevy::prelude::*;
+/// # use bevy_mod_opacity::{Opacity, Toast};
+/// # let mut world = World::new();
+/// let entity = world.spawn((Text::new("Saved"), Toast::new(0.2, 2.0, 0.5))).id();
+/// assert_eq!(world.get::<Opacity>(entity), Some(&Opacity::INVISIBLE));
+/// ```
+#[derive(Debug, Clone, Copy, Component)]
+#[require(Opacity(||Opacity::INVISIBLE))]
+pub struct Toast {
+    pub(crate) fade_in: FadeIn,
+    pub(crate) hold: f32,
+    pub(crate) fade_out: FadeOut,
+    pub(crate) phase: ToastPhase,
+}
+
+/// Current phase of a [`Toast`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ToastPhase {
+    #[default]
+    FadeIn,
+    Hold,
+    FadeOut,
 }
 
 impl FadeIn {
@@ -38,17 +274,166 @@ impl FadeIn {
             current: 0.,
             time,
             curve: None,
+            start: None,
+            from_current: false,
+            despawns: false,
+        }
+    }
+
+    /// Fade from the current opacity to `1.0` in exactly `time`,
+    /// instead of the remaining fraction of it.
+    ///
+    /// Unlike [`FadeIn::new`], the rate depends on the starting opacity,
+    /// re-showing a partially visible element takes as long as showing a hidden one.
+    /// This is kept when a curve is set afterwards, the curve is scaled to start from the current opacity.
+    pub fn from_current(time: f32) -> Self {
+        FadeIn {
+            from_current: true,
+            ..FadeIn::new(time)
+        }
+    }
+
+    /// Returns the curve used by [`FadeIn::step`],
+    /// [`FadeIn::from_current`] without a curve fades linearly in exactly `time`.
+    fn effective_curve(&self) -> Option<CurveFn> {
+        match self.curve {
+            None if self.from_current => Some(CurveFn::Preset(FadeCurve::Linear)),
+            curve => curve,
         }
     }
 
+    /// Despawn this entity and all its children once opacity reaches `1.0`.
+    pub fn despawning(mut self) -> Self {
+        self.despawns = true;
+        self
+    }
+
+    /// Set whether this entity and all its children are despawned once opacity reaches `1.0`.
+    pub fn set_despawns(&mut self, despawns: bool) {
+        self.despawns = despawns;
+    }
+
     /// Set a curve for fading.
     ///
     /// Curve maps a value in `0..1` to a value in `0..1`,
-    /// for example `|x| x`.
+    /// for example `|x| x`, and is scaled to start from the existing opacity.
     pub fn with_curve(mut self, curve: fn(f32) -> f32) -> Self {
-        self.curve = Some(curve);
+        self.curve = Some(CurveFn::Normalized(curve));
+        self
+    }
+
+    /// Set a curve for fading in absolute time.
+    ///
+    /// Curve maps elapsed seconds and the duration to a value in `0..1`,
+    /// and is scaled like [`FadeIn::with_curve`]. Unlike normalized curves its shape does not
+    /// need to stretch with the duration, for example `|t, d| ((t - d + 0.5) / 0.5).clamp(0., 1.)`
+    /// waits and then fades during the last half second.
+    ///
+    /// The fade completes once the curve reaches `1.0`. Reach it exactly at the duration,
+    /// [`FadeIn::time_to_target`] assumes the fade ends there.
+    pub fn with_curve_secs(mut self, curve: fn(f32, f32) -> f32) -> Self {
+        self.curve = Some(CurveFn::Secs(curve));
         self
     }
+
+    /// Set a preset curve for fading.
+    pub fn with_fade_curve(mut self, curve: FadeCurve) -> Self {
+        self.curve = Some(CurveFn::Preset(curve));
+        self
+    }
+
+    /// Set one of bevy's easing functions for fading, like `EaseFunction::BounceOut`.
+    pub fn with_ease(self, ease: EaseFunction) -> Self {
+        self.with_fade_curve(FadeCurve::Ease(ease))
+    }
+
+    /// Returns the opacity this fade ends at, always `1.0`.
+    pub fn target(&self) -> f32 {
+        1.
+    }
+
+    /// Returns the average change in opacity per second from `0.0`,
+    /// the exact rate without a curve.
+    ///
+    /// Returns [`f32::INFINITY`] if the fade completes immediately,
+    /// with a time of `0.0` or less, or `NaN`.
+    ///
+    /// ```
+    /// # use bevy_mod_opacity::FadeIn;
+    /// assert_eq!(FadeIn::new(2.0).speed(), 0.5);
+    /// assert_eq!(FadeIn::new(0.).speed(), f32::INFINITY);
+    /// ```
+    pub fn speed(&self) -> f32 {
+        if self.time <= 0. || self.time.is_nan() {
+            return f32::INFINITY;
+        }
+        1. / self.time
+    }
+
+    /// Returns `true` if the entity is despawned once the fade completes,
+    /// unless disabled by [`FadeConfig::despawn_on_fade_out`].
+    ///
+    /// ```
+    /// # use bevy_mod_opacity::FadeIn;
+    /// assert!(!FadeIn::new(1.0).will_despawn());
+    /// assert!(FadeIn::new(1.0).despawning().will_despawn());
+    /// ```
+    pub fn will_despawn(&self) -> bool {
+        self.despawns
+    }
+
+    /// Returns the seconds remaining until opacity reaches `1.0`,
+    /// or `None` if the fade does not progress, for example with an infinite time.
+    ///
+    /// Without a curve this is computed from the current opacity, so external modification
+    /// is taken into account. With a curve this is the time until the duration ends,
+    /// see [`FadeIn::with_curve_secs`]. The result is never negative.
+    pub fn time_to_target(&self, opacity: &Opacity) -> Option<f32> {
+        if self.time <= 0. || self.time.is_nan() {
+            return Some(0.);
+        }
+        if !self.time.is_finite() {
+            return None;
+        }
+        let remaining = match self.effective_curve() {
+            Some(_) => self.time - self.current,
+            None => (1. - opacity.0) * self.time,
+        };
+        Some(remaining.max(0.))
+    }
+
+    /// Advance the fade by `dt` seconds, returns `true` if the fade has completed
+    /// and this component should be removed, or the entity despawned if [`FadeIn::despawning`].
+    ///
+    /// This is what the built-in system runs every frame,
+    /// use this to drive a fade manually outside of the schedule.
+    pub fn step(&mut self, opacity: &mut Opacity, dt: f32) -> bool {
+        if self.time <= 0. || self.time.is_nan() {
+            opacity.0 = 1.;
+            return true;
+        }
+        let curve = self.effective_curve();
+        // Without a curve we can make this work with external modification.
+        if let Some(curve) = curve {
+            let start = *self.start.get_or_insert(opacity.0);
+            self.current += dt;
+            opacity.0 = Opacity::lerp_unclamped(start, 1.0, curve.sample(self.current, self.time));
+        } else {
+            let offset = dt / self.time;
+            opacity.0 += offset;
+        }
+        // Easing functions like `BounceOut` touch the end value before the fade is done.
+        let done = match curve {
+            Some(CurveFn::Preset(_)) => self.current >= self.time,
+            _ => opacity.0 >= 1.,
+        };
+        if done {
+            opacity.0 = 1.;
+            true
+        } else {
+            false
+        }
+    }
 }
 
 impl FadeOut {
@@ -57,59 +442,603 @@ impl FadeOut {
             current: 0.,
             time,
             curve: None,
+            filter: None,
+            start: None,
+            target: 0.,
+        }
+    }
+
+    /// Stop at the given opacity instead of `0.0`, clamped to `0..=1`.
+    ///
+    /// The entity is still despawned once the target is reached, even though it is visible,
+    /// use [`FadeOut::despawn_marked`] or [`FadeConfig::despawn_on_fade_out`] to keep it.
+    ///
+    /// ```
+    /// # use bevy_mod_opacity::FadeOut;
+    /// assert_eq!(FadeOut::new(1.0).with_target(-0.5).target(), 0.0);
+    /// assert_eq!(FadeOut::new(1.0).with_target(2.0).target(), 1.0);
+    /// ```
+    pub fn with_target(mut self, target: f32) -> Self {
+        self.target = target.clamp(0., 1.);
+        self
+    }
+
+    /// Only despawn this entity and its descendants with component `M` afterwards.
+    ///
+    /// Other entities remain at opacity `0.0` and this component is removed.
+    pub fn despawn_marked<M: Component>(mut self) -> Self {
+        self.filter = Some(|world, entity| world.get::<M>(entity).is_some());
+        self
+    }
+
+    /// Set [`NextState`] to `state` once the fade completes, before despawning.
+    ///
+    /// ```
+    /// # use bevy::prelude::*;
+    /// # use bevy_mod_opacity::FadeOut;
+    /// #[derive(Debug, Clone, PartialEq, Eq, Hash, Default, States)]
+    /// enum Screen {
+    ///     #[default]
+    ///     Menu,
+    ///     Game,
+    /// }
+    ///
+    /// # let mut world = World::new();
+    /// world.spawn((Sprite::default(), FadeOut::new(1.0).then_set_state(Screen::Game)));
+    /// ```
+    #[cfg(feature = "state")]
+    pub fn then_set_state<S: FreelyMutableState>(self, state: S) -> FadeOutThen {
+        self.then(move |world| {
+            if let Some(mut next) = world.get_resource_mut::<NextState<S>>() {
+                next.set(state.clone());
+            }
+        })
+    }
+
+    /// Run `command` once the fade completes, before despawning.
+    pub fn then(self, command: impl Fn(&mut World) + Send + Sync + 'static) -> FadeOutThen {
+        FadeOutThen {
+            fade: self,
+            then: Then(Arc::new(command)),
         }
     }
 
     /// Set a curve for fading.
     ///
     /// Curve maps a value in `0..1` to a value in `0..1`,
-    /// for example `|x| x`, does not need to be reversed.
+    /// for example `|x| x`, does not need to be reversed, and is scaled to start from the existing opacity.
     pub fn with_curve(mut self, curve: fn(f32) -> f32) -> Self {
-        self.curve = Some(curve);
+        self.curve = Some(CurveFn::Normalized(curve));
+        self
+    }
+
+    /// Set a curve for fading in absolute time.
+    ///
+    /// Curve maps elapsed seconds and the duration to a value in `0..1`,
+    /// does not need to be reversed, and should reach `1.0` at the duration,
+    /// see [`FadeIn::with_curve_secs`].
+    pub fn with_curve_secs(mut self, curve: fn(f32, f32) -> f32) -> Self {
+        self.curve = Some(CurveFn::Secs(curve));
         self
     }
+
+    /// Set a preset curve for fading.
+    pub fn with_fade_curve(mut self, curve: FadeCurve) -> Self {
+        self.curve = Some(CurveFn::Preset(curve));
+        self
+    }
+
+    /// Set one of bevy's easing functions for fading, like `EaseFunction::BounceOut`.
+    pub fn with_ease(self, ease: EaseFunction) -> Self {
+        self.with_fade_curve(FadeCurve::Ease(ease))
+    }
+
+    /// Returns the opacity this fade ends at, `0.0` unless set by [`FadeOut::with_target`].
+    ///
+    /// ```
+    /// # use bevy_mod_opacity::FadeOut;
+    /// assert_eq!(FadeOut::new(1.0).with_target(0.2).target(), 0.2);
+    /// ```
+    pub fn target(&self) -> f32 {
+        self.target
+    }
+
+    /// Returns the average change in opacity per second from `1.0` to the target,
+    /// the exact rate without a curve.
+    ///
+    /// Returns [`f32::INFINITY`] if the fade completes immediately,
+    /// with a time of `0.0` or less, or `NaN`.
+    ///
+    /// ```
+    /// # use bevy_mod_opacity::FadeOut;
+    /// assert_eq!(FadeOut::new(2.0).speed(), 0.5);
+    /// assert_eq!(FadeOut::new(1.0).with_target(0.5).speed(), 0.5);
+    /// assert_eq!(FadeOut::new(0.).speed(), f32::INFINITY);
+    /// ```
+    pub fn speed(&self) -> f32 {
+        if self.time <= 0. || self.time.is_nan() {
+            return f32::INFINITY;
+        }
+        (1. - self.target) / self.time
+    }
+
+    /// Returns `true` if the entity itself is despawned once the fade completes,
+    /// unless disabled by [`FadeConfig::despawn_on_fade_out`].
+    ///
+    /// Returns `false` with [`FadeOut::despawn_marked`], which only despawns marked entities.
+    ///
+    /// ```
+    /// # use bevy::prelude::*;
+    /// # use bevy_mod_opacity::FadeOut;
+    /// # #[derive(Component)]
+    /// # struct Marker;
+    /// assert!(FadeOut::new(1.0).will_despawn());
+    /// assert!(!FadeOut::new(1.0).despawn_marked::<Marker>().will_despawn());
+    /// ```
+    pub fn will_despawn(&self) -> bool {
+        self.filter.is_none()
+    }
+
+    /// Returns the seconds remaining until opacity reaches the target,
+    /// or `None` if the fade does not progress, for example with an infinite time.
+    ///
+    /// Returns `Some(0.0)` with a target of `1.0`, which completes immediately.
+    ///
+    /// Without a curve this is computed from the current opacity, so external modification
+    /// is taken into account. With a curve this is the time until the duration ends,
+    /// see [`FadeIn::with_curve_secs`]. The result is never negative.
+    pub fn time_to_target(&self, opacity: &Opacity) -> Option<f32> {
+        let range = 1.0 - self.target;
+        if self.time <= 0. || self.time.is_nan() || range <= 0. {
+            return Some(0.);
+        }
+        if !self.time.is_finite() {
+            return None;
+        }
+        let remaining = match self.curve {
+            Some(_) => self.time - self.current,
+            None => (opacity.0 - self.target) / range * self.time,
+        };
+        Some(remaining.max(0.))
+    }
+
+    /// Advance the fade by `dt` seconds, returns `true` if the fade has completed
+    /// and the entity should be despawned.
+    ///
+    /// This is what the built-in system runs every frame,
+    /// use this to drive a fade manually outside of the schedule.
+    pub fn step(&mut self, opacity: &mut Opacity, dt: f32) -> bool {
+        let range = 1.0 - self.target;
+        // There is nothing to fade with a target of `1.0`, even with a curve.
+        if self.time <= 0. || self.time.is_nan() || range <= 0. {
+            opacity.0 = opacity.0.min(self.target);
+            return true;
+        }
+        // Opacity at or below the target is never raised, the fade completes as is.
+        if opacity.0 <= self.target && (self.curve.is_none() || self.start.is_none()) {
+            return true;
+        }
+        // Without a curve we can make this work with external modification.
+        if let Some(curve) = self.curve {
+            let start = *self.start.get_or_insert(opacity.0);
+            self.current += dt;
+            opacity.0 =
+                Opacity::lerp_unclamped(start, self.target, curve.sample(self.current, self.time));
+        } else {
+            let offset = dt / self.time * range;
+            opacity.0 -= offset;
+        }
+        let done = match self.curve {
+            Some(CurveFn::Preset(_)) => self.current >= self.time,
+            _ => opacity.0 <= self.target,
+        };
+        if done {
+            opacity.0 = self.target;
+            true
+        } else {
+            false
+        }
+    }
+}
+
+/// A [`FadeOut`] that runs a command once it completes, see [`FadeOut::then`].
+///
+/// The command is stored in a separate component, so [`FadeOut`] stays [`Copy`].
+#[derive(Debug, Clone, Bundle)]
+pub struct FadeOutThen {
+    fade: FadeOut,
+    then: Then,
+}
+
+impl FadeOutThen {
+    /// See [`FadeOut::with_target`].
+    pub fn with_target(mut self, target: f32) -> Self {
+        self.fade = self.fade.with_target(target);
+        self
+    }
+
+    /// See [`FadeOut::despawn_marked`].
+    pub fn despawn_marked<M: Component>(mut self) -> Self {
+        self.fade = self.fade.despawn_marked::<M>();
+        self
+    }
+
+    /// See [`FadeOut::with_curve`].
+    pub fn with_curve(mut self, curve: fn(f32) -> f32) -> Self {
+        self.fade = self.fade.with_curve(curve);
+        self
+    }
+
+    /// See [`FadeOut::with_curve_secs`].
+    pub fn with_curve_secs(mut self, curve: fn(f32, f32) -> f32) -> Self {
+        self.fade = self.fade.with_curve_secs(curve);
+        self
+    }
+
+    /// See [`FadeOut::with_fade_curve`].
+    pub fn with_fade_curve(mut self, curve: FadeCurve) -> Self {
+        self.fade = self.fade.with_fade_curve(curve);
+        self
+    }
+
+    /// See [`FadeOut::with_ease`].
+    pub fn with_ease(mut self, ease: EaseFunction) -> Self {
+        self.fade = self.fade.with_ease(ease);
+        self
+    }
+}
+
+impl Blink {
+    pub fn new(times: u32, period: f32) -> Self {
+        Blink {
+            remaining: times,
+            period,
+            current: 0.,
+            low: 0.,
+        }
+    }
+
+    /// Set the opacity during the low half of each blink, by default `0.0`.
+    pub fn with_low(mut self, low: f32) -> Self {
+        self.low = low;
+        self
+    }
+
+    /// Number of blinks remaining, including the current one.
+    pub fn remaining(&self) -> u32 {
+        self.remaining
+    }
+
+    /// Advance the blink by `dt` seconds, returns `true` if all blinks have completed
+    /// and this component should be removed.
+    pub fn step(&mut self, opacity: &mut Opacity, dt: f32) -> bool {
+        self.current += dt;
+        while self.remaining > 0 && self.current >= self.period {
+            self.current -= self.period;
+            self.remaining -= 1;
+        }
+        if self.remaining == 0 {
+            opacity.0 = 1.;
+            return true;
+        }
+        opacity.0 = if self.current < self.period / 2. {
+            self.low
+        } else {
+            1.
+        };
+        false
+    }
 }
 
-pub fn fade_in(
+impl Toast {
+    pub fn new(fade_in: f32, hold: f32, fade_out: f32) -> Self {
+        Toast {
+            fade_in: FadeIn::new(fade_in),
+            hold,
+            fade_out: FadeOut::new(fade_out),
+            phase: ToastPhase::FadeIn,
+        }
+    }
+
+    /// Returns the current phase.
+    pub fn phase(&self) -> ToastPhase {
+        self.phase
+    }
+
+    /// Advance the toast by `dt` seconds, returns `true` if it has faded out
+    /// and should be despawned.
+    pub fn step(&mut self, opacity: &mut Opacity, dt: f32) -> bool {
+        match self.phase {
+            ToastPhase::FadeIn => {
+                if self.fade_in.step(opacity, dt) {
+                    self.phase = ToastPhase::Hold;
+                }
+                false
+            }
+            ToastPhase::Hold => {
+                self.hold -= dt;
+                if self.hold <= 0. {
+                    self.phase = ToastPhase::FadeOut;
+                }
+                false
+            }
+            ToastPhase::FadeOut => self.fade_out.step(opacity, dt),
+        }
+    }
+}
+
+/// [`Event`] sent when a [`FadeIn`] or [`FadeOut`] finishes,
+/// before the entity is despawned if applicable.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Event)]
+pub enum FadeCompleted {
+    In(Entity),
+    Out(Entity),
+}
+
+impl FadeCompleted {
+    /// Returns the entity that finished fading.
+    pub fn entity(&self) -> Entity {
+        match self {
+            FadeCompleted::In(entity) | FadeCompleted::Out(entity) => *entity,
+        }
+    }
+}
+
+/// [`QueryData`] for checking whether a built-in driver is animating an entity's [`Opacity`],
+/// for example to ignore input during transitions.
+///
+/// ```
+/// # use bevy::prelude::*;
+/// # use bevy_mod_opacity::{FadeOut, FadeState};
+/// # let mut world = World::new();
+/// let entity = world.spawn(FadeOut::new(1.0)).id();
+/// let mut query = world.query::<FadeState>();
+/// let state = query.get(&world, entity).unwrap();
+/// assert!(state.is_animating());
+/// assert!(state.is_fading_out());
+/// assert!(!state.is_fading_in());
+/// ```
+#[derive(Debug, QueryData)]
+pub struct FadeState {
+    fade_in: Has<FadeIn>,
+    fade_out: Has<FadeOut>,
+    blink: Has<Blink>,
+    track: Has<OpacityTrack>,
+    follow: Has<OpacityFollow>,
+    toast: Has<Toast>,
+}
+
+impl FadeStateItem<'_> {
+    /// Returns `true` if any of [`FadeIn`], [`FadeOut`], [`Blink`], [`OpacityTrack`],
+    /// [`OpacityFollow`] or [`Toast`] is present.
+    ///
+    /// ```
+    /// # use bevy::prelude::*;
+    /// # use bevy_mod_opacity::{FadeState, Opacity};
+    /// # let mut world = World::new();
+    /// let entity = world.spawn(Opacity(0.5)).id();
+    /// let mut query = world.query::<FadeState>();
+    /// assert!(!query.get(&world, entity).unwrap().is_animating());
+    /// ```
+    pub fn is_animating(&self) -> bool {
+        self.fade_in || self.fade_out || self.blink || self.track || self.follow || self.toast
+    }
+
+    /// Returns `true` if a [`FadeIn`] is present.
+    ///
+    /// ```
+    /// # use bevy::prelude::*;
+    /// # use bevy_mod_opacity::{FadeIn, FadeState};
+    /// # let mut world = World::new();
+    /// let entity = world.spawn(FadeIn::new(1.0)).id();
+    /// let mut query = world.query::<FadeState>();
+    /// assert!(query.get(&world, entity).unwrap().is_fading_in());
+    /// ```
+    pub fn is_fading_in(&self) -> bool {
+        self.fade_in
+    }
+
+    /// Returns `true` if a [`FadeOut`] is present.
+    pub fn is_fading_out(&self) -> bool {
+        self.fade_out
+    }
+}
+
+pub fn fade_in<T: Default + Send + Sync + 'static>(
     mut commands: Commands,
-    time: Res<Time<Virtual>>,
+    time: Res<Time<T>>,
+    config: Res<FadeConfig>,
+    mut events: EventWriter<FadeCompleted>,
     mut query: Query<(Entity, &mut FadeIn, &mut Opacity)>,
 ) {
     let dt = time.delta_secs();
     for (entity, mut fade_in, mut opacity) in &mut query {
-        // Without a curve we can make this work with external modification.
-        if let Some(curve) = fade_in.curve {
-            fade_in.current += dt;
-            opacity.0 = curve(fade_in.current / fade_in.time);
-        } else {
-            let offset = dt / fade_in.time;
-            opacity.0 += offset;
+        if !fade_in.step(&mut opacity, dt) {
+            continue;
         }
-        if opacity.0 > 1. {
-            opacity.0 = 1.;
+        events.send(FadeCompleted::In(entity));
+        if fade_in.despawns && config.despawn_on_fade_out {
+            config.despawn_mode.despawn::<FadeIn>(&mut commands, entity);
+        } else {
             commands.entity(entity).remove::<FadeIn>();
         }
     }
 }
 
-pub fn fade_out(
+pub fn fade_out<T: Default + Send + Sync + 'static>(
     mut commands: Commands,
-    time: Res<Time<Virtual>>,
-    mut query: Query<(Entity, &mut FadeOut, &mut Opacity)>,
+    time: Res<Time<T>>,
+    config: Res<FadeConfig>,
+    mut events: EventWriter<FadeCompleted>,
+    mut query: Query<(Entity, &mut FadeOut, &mut Opacity, Option<&Then>)>,
+    floors: Query<(), With<OpacityFloor>>,
 ) {
     let dt = time.delta_secs();
-    for (entity, mut fade_out, mut opacity) in &mut query {
-        // Without a curve we can make this work with external modification.
-        if let Some(curve) = fade_out.curve {
-            fade_out.current += dt;
-            opacity.0 = 1.0 - curve(fade_out.current / fade_out.time);
+    for (entity, mut fade_out, mut opacity, then) in &mut query {
+        if !fade_out.step(&mut opacity, dt) {
+            continue;
+        }
+        events.send(FadeCompleted::Out(entity));
+        if let Some(Then(then)) = then.cloned() {
+            commands.entity(entity).remove::<Then>();
+            commands.queue(move |world: &mut World| then(world));
+        }
+        match fade_out.filter {
+            // A floor keeps the entity visible, so it is never despawned.
+            _ if !config.despawn_on_fade_out || floors.contains(entity) => {
+                commands.entity(entity).remove::<FadeOut>();
+            }
+            None => config
+                .despawn_mode
+                .despawn::<FadeOut>(&mut commands, entity),
+            Some(filter) => {
+                commands.entity(entity).remove::<FadeOut>();
+                commands.queue(move |world: &mut World| despawn_marked(world, entity, filter));
+            }
+        }
+    }
+}
+
+pub fn toast<T: Default + Send + Sync + 'static>(
+    mut commands: Commands,
+    time: Res<Time<T>>,
+    config: Res<FadeConfig>,
+    mut events: EventWriter<FadeCompleted>,
+    mut query: Query<(Entity, &mut Toast, &mut Opacity)>,
+) {
+    let dt = time.delta_secs();
+    for (entity, mut toast, mut opacity) in &mut query {
+        if !toast.step(&mut opacity, dt) {
+            continue;
+        }
+        events.send(FadeCompleted::Out(entity));
+        if config.despawn_on_fade_out {
+            config.despawn_mode.despawn::<Toast>(&mut commands, entity);
         } else {
-            let offset = dt / fade_out.time;
-            opacity.0 -= offset;
+            commands.entity(entity).remove::<Toast>();
         }
-        if opacity.0 <= 0. {
-            opacity.0 = 0.;
-            commands.entity(entity).despawn_recursive();
+    }
+}
+
+pub fn blink<T: Default + Send + Sync + 'static>(
+    mut commands: Commands,
+    time: Res<Time<T>>,
+    mut query: Query<(Entity, &mut Blink, &mut Opacity)>,
+) {
+    let dt = time.delta_secs();
+    for (entity, mut blink, mut opacity) in &mut query {
+        if blink.step(&mut opacity, dt) {
+            commands.entity(entity).remove::<Blink>();
+        }
+    }
+}
+
+/// Extension methods on [`Commands`] for fading.
+pub trait OpacityCommands {
+    /// Fade out and despawn `root` and its descendants within the given time.
+    ///
+    /// Existing [`Opacity`] on the root is kept and faded from.
+    /// [`FadeIn`], [`Blink`], [`OpacityTrack`], [`OpacityFollow`] and [`Toast`] are removed
+    /// from the root and every descendant, so they do not fight the fade.
+    /// A running [`FadeOut`] on the root is replaced, one on a descendant keeps running,
+    /// so commands added by [`FadeOut::then`] still run.
+    fn fade_out_subtree(&mut self, root: Entity, time: f32);
+
+    /// Queue [`apply_opacity_now`], applying opacity when commands are flushed
+    /// instead of waiting for `PostUpdate`.
+    fn apply_opacity_now(&mut self);
+
+    /// Insert or remove [`OpacityDisabled`] on `entity`, keeping its [`Opacity`] value.
+    fn set_opacity_enabled(&mut self, entity: Entity, enabled: bool);
+
+    /// Spawn `root` with a child for each of `children`, fading out together within the given time,
+    /// for particle bursts and similar effects. Returns the root.
+    ///
+    /// The group is despawned according to [`FadeConfig::despawn_mode`],
+    /// by default the root and every child.
+    fn spawn_fading_group<B: Bundle>(
+        &mut self,
+        root: impl Bundle,
+        children: impl IntoIterator<Item = B>,
+        time: f32,
+    ) -> Entity;
+}
+
+impl OpacityCommands for Commands<'_, '_> {
+    fn fade_out_subtree(&mut self, root: Entity, time: f32) {
+        self.queue(move |world: &mut World| {
+            let mut stack = vec![root];
+            while let Some(entity) = stack.pop() {
+                let Ok(mut entity) = world.get_entity_mut(entity) else {
+                    continue;
+                };
+                if let Some(children) = entity.get::<Children>() {
+                    stack.extend(children.iter().copied());
+                }
+                entity.remove::<Writers>();
+            }
+            if let Ok(mut root) = world.get_entity_mut(root) {
+                root.insert(FadeOut::new(time));
+            }
+        });
+    }
+
+    fn apply_opacity_now(&mut self) {
+        self.queue(apply_opacity_now);
+    }
+
+    fn set_opacity_enabled(&mut self, entity: Entity, enabled: bool) {
+        if enabled {
+            self.entity(entity).remove::<OpacityDisabled>();
+        } else {
+            self.entity(entity).insert(OpacityDisabled);
+        }
+    }
+
+    fn spawn_fading_group<B: Bundle>(
+        &mut self,
+        root: impl Bundle,
+        children: impl IntoIterator<Item = B>,
+        time: f32,
+    ) -> Entity {
+        self.spawn((root, FadeOut::new(time)))
+            .with_children(|parent| {
+                for child in children {
+                    parent.spawn(child);
+                }
+            })
+            .id()
+    }
+}
+
+/// Built-in components driving [`Opacity`] over time, other than [`FadeOut`].
+type Writers = (FadeIn, Blink, OpacityTrack, OpacityFollow, Toast);
+
+/// Built-in components driving [`Opacity`] over time.
+type Drivers = (FadeOut, Then, Writers);
+
+/// Remove built-in fading components from an entity and reset its opacity to `1.0`,
+/// for recycling pooled entities.
+pub fn reset_fade(commands: &mut Commands, entity: Entity) {
+    commands
+        .entity(entity)
+        .remove::<Drivers>()
+        .insert(Opacity::OPAQUE);
+}
+
+fn despawn_marked(world: &mut World, root: Entity, filter: fn(&World, Entity) -> bool) {
+    let mut marked = Vec::new();
+    let mut stack = vec![root];
+    while let Some(entity) = stack.pop() {
+        if filter(world, entity) {
+            marked.push(entity);
+        } else if let Some(children) = world.get::<Children>(entity) {
+            stack.extend(children.iter().copied());
+        }
+    }
+    for entity in marked {
+        if let Ok(entity) = world.get_entity_mut(entity) {
+            entity.despawn_recursive();
         }
     }
 }
@@ -0,0 +1,78 @@
+use bevy::{
+    prelude::{Component, DetectChangesMut, Query, Res},
+    time::Time,
+};
+
+use crate::Opacity;
+
+/// Continuously moves [`Opacity`] towards a target with a critically damped spring.
+///
+/// Velocity is kept when the target changes, so following a fluctuating target
+/// does not cause sudden changes in speed. This component is never removed automatically,
+/// but stops writing [`Opacity`] once it settles at the target.
+///
+/// If [`Opacity`] is not present, insert at `1.0`.
+#[derive(Debug, Clone, Copy, Component)]
+#[require(Opacity)]
+pub struct OpacityFollow {
+    /// Value opacity moves towards.
+    pub target: f32,
+    /// Angular frequency of the spring, higher values reach the target faster.
+    pub frequency: f32,
+    pub(crate) velocity: f32,
+}
+
+impl OpacityFollow {
+    pub fn new(target: f32, frequency: f32) -> Self {
+        OpacityFollow {
+            target,
+            frequency,
+            velocity: 0.,
+        }
+    }
+
+    /// Change the target, keeping the current velocity.
+    pub fn set_target(&mut self, target: f32) {
+        self.target = target;
+    }
+
+    /// Current rate of change of opacity per second.
+    pub fn velocity(&self) -> f32 {
+        self.velocity
+    }
+
+    /// Advance the spring by `dt` seconds.
+    pub fn step(&mut self, opacity: &mut Opacity, dt: f32) {
+        let omega = self.frequency;
+        let offset = opacity.0 - self.target;
+        let decay = (-omega * dt).exp();
+        let temp = (self.velocity + omega * offset) * dt;
+        self.velocity = (self.velocity - omega * temp) * decay;
+        opacity.0 = self.target + (offset + temp) * decay;
+    }
+}
+
+/// Distance and velocity at which the spring snaps to its target and stops writing.
+const SETTLE_EPSILON: f32 = 1e-4;
+
+pub fn opacity_follow<T: Default + Send + Sync + 'static>(
+    time: Res<Time<T>>,
+    mut query: Query<(&mut OpacityFollow, &mut Opacity)>,
+) {
+    let dt = time.delta_secs();
+    for (mut follow, mut opacity) in &mut query {
+        // Settled springs do not mark either component as changed.
+        if follow.velocity.abs() < SETTLE_EPSILON
+            && (opacity.0 - follow.target).abs() < SETTLE_EPSILON
+        {
+            if follow.velocity != 0. {
+                follow.velocity = 0.;
+            }
+            opacity.set_if_neq(Opacity(follow.target));
+            continue;
+        }
+        let mut value = *opacity;
+        follow.step(&mut value, dt);
+        opacity.set_if_neq(value);
+    }
+}
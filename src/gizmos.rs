@@ -0,0 +1,47 @@
+use bevy::{
+    app::{App, Plugin},
+    color::Alpha,
+    gizmos::aabb::ShowAabbGizmo,
+    prelude::IntoSystemConfigs,
+};
+
+use crate::{
+    add_opacity_systems, calculate_opacity,
+    impls::{record_base_alpha, BaseAlphaQuery, ColorComponent},
+    OpacityExtension, OpacitySet,
+};
+
+/// Only a manually set color is affected, the config group's default color is not.
+impl ColorComponent for ShowAabbGizmo {
+    fn alpha(&self) -> f32 {
+        self.color.map(|x| x.alpha()).unwrap_or(1.)
+    }
+
+    fn set_alpha(&mut self, alpha: f32) {
+        if let Some(color) = &mut self.color {
+            color.set_alpha(alpha);
+        }
+    }
+}
+
+/// Added by [`OpacityExtension::register_opacity_gizmos`], registering again does nothing.
+#[derive(Debug)]
+pub(crate) struct GizmosOpacityPlugin;
+
+impl Plugin for GizmosOpacityPlugin {
+    fn build(&self, app: &mut App) {
+        if app.is_plugin_added::<Self>() {
+            return;
+        }
+        add_opacity_systems(app, || {
+            record_base_alpha::<ShowAabbGizmo>
+                .after(calculate_opacity)
+                .in_set(OpacitySet::Calculate)
+        });
+        app.register_opacity::<BaseAlphaQuery<ShowAabbGizmo>>();
+    }
+
+    fn is_unique(&self) -> bool {
+        false
+    }
+}
@@ -0,0 +1,66 @@
+use std::marker::PhantomData;
+
+use bevy::{
+    asset::{Asset, AssetId, Assets},
+    ecs::{
+        component::Tick,
+        query::QueryData,
+        system::{SystemChangeTick, SystemParamItem},
+    },
+    prelude::{Component, Local, ResMut},
+    utils::HashMap,
+};
+
+use crate::{OpacityAsset, OpacityQuery};
+
+/// Assets written in the current apply pass, so an asset shared by many entities
+/// under the same opacity is only mutated once per frame.
+#[derive(bevy::ecs::system::SystemParam)]
+pub struct AppliedAssets<'s, A: Asset> {
+    ticks: SystemChangeTick,
+    applied: Local<'s, (Tick, HashMap<AssetId<A>, f32>)>,
+}
+
+impl<A: Asset> AppliedAssets<'_, A> {
+    /// Returns `false` if `id` was already written with `opacity` in this pass.
+    pub fn insert(&mut self, id: AssetId<A>, opacity: f32) -> bool {
+        let (tick, applied) = &mut *self.applied;
+        if *tick != self.ticks.this_run() {
+            *tick = self.ticks.this_run();
+            applied.clear();
+        }
+        applied.insert(id, opacity) != Some(opacity)
+    }
+}
+
+/// Write opacity to an [`OpacityAsset`] unless it was already written with the same value this frame.
+pub(crate) fn apply_asset_opacity<A: OpacityAsset>(
+    (assets, applied): &mut SystemParamItem<(ResMut<Assets<A>>, AppliedAssets<A>)>,
+    id: AssetId<A>,
+    opacity: f32,
+) {
+    if !applied.insert(id, opacity) {
+        return;
+    }
+    if let Some(asset) = assets.get_mut(id) {
+        asset.apply_opacity(opacity);
+    }
+}
+
+/// [`QueryData`] of a component holding a handle to an [`OpacityAsset`].
+#[derive(Debug, QueryData)]
+pub struct OpacityAssetQuery<A: OpacityAsset, C: Component> {
+    pub handle: &'static C,
+    marker: PhantomData<A>,
+}
+
+impl<A: OpacityAsset, C: Component> OpacityQuery for OpacityAssetQuery<A, C>
+where
+    for<'t> &'t C: Into<AssetId<A>>,
+{
+    type Cx = (ResMut<'static, Assets<A>>, AppliedAssets<'static, A>);
+
+    fn apply_opacity(this: &mut Self::Item<'_>, cx: &mut SystemParamItem<Self::Cx>, opacity: f32) {
+        apply_asset_opacity(cx, this.handle.into(), opacity)
+    }
+}
@@ -1,46 +1,300 @@
+use std::{
+    cmp::Ordering,
+    fmt::{self, Debug, Formatter},
+    marker::PhantomData,
+};
+
 use bevy::{
-    asset::Assets,
-    color::Alpha,
-    ecs::{query::QueryData, system::SystemParam},
-    pbr::{Material, MeshMaterial3d, StandardMaterial},
+    asset::{Asset, AssetEvent, AssetId, Assets, UntypedAssetId},
+    color::{Alpha, Color, LinearRgba},
+    ecs::{
+        query::{Has, QueryData},
+        system::SystemParam,
+    },
+    pbr::{ExtendedMaterial, Material, MaterialExtension, MeshMaterial3d, StandardMaterial},
     prelude::ImageNode,
-    prelude::{Component, ResMut},
+    prelude::{
+        AlphaMode, Commands, Component, Entity, EventReader, Query, Res, ResMut, Resource, Without,
+    },
+    render::{
+        camera::{Camera, ClearColorConfig},
+        view::InheritedVisibility,
+    },
     sprite::{ColorMaterial, Material2d, MeshMaterial2d, Sprite},
     text::TextColor,
-    ui::{BackgroundColor, BorderColor},
+    ui::{BackgroundColor, BorderColor, BoxShadow, MaterialNode, Outline, UiMaterial},
+    utils::HashMap,
+};
+
+use crate::{
+    handle::{apply_asset_opacity, AppliedAssets},
+    OpacityAsset, OpacityBaseMaterial, OpacityMap, OpacityMaterialExtension, OpacityQuery,
+    SkipHidden,
 };
 
-use crate::{OpacityAsset, OpacityQuery};
+/// Designed alpha of an entity's color component `C`, like [`Sprite`], [`ImageNode`],
+/// [`TextColor`] or a [`BoxShadow`] with [`FadeBoxShadow`], opacity is multiplied into this value instead of replacing the alpha.
+///
+/// Each component has its own base, so an entity with both an [`ImageNode`] and a [`BoxShadow`]
+/// keeps the designed alpha of each. Recorded automatically the first time an entity
+/// is affected by opacity. If the color is modified elsewhere afterwards,
+/// its new alpha becomes the designed alpha.
+#[derive(Component)]
+pub struct BaseAlpha<C: Component>(pub f32, pub PhantomData<C>);
+
+impl<C: Component> BaseAlpha<C> {
+    pub fn new(alpha: f32) -> Self {
+        BaseAlpha(alpha, PhantomData)
+    }
+}
+
+impl<C: Component> Debug for BaseAlpha<C> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("BaseAlpha").field(&self.0).finish()
+    }
+}
+
+impl<C: Component> Clone for BaseAlpha<C> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<C: Component> Copy for BaseAlpha<C> {}
+
+impl<C: Component> PartialEq for BaseAlpha<C> {
+    fn eq(&self, other: &Self) -> bool {
+        self.0 == other.0
+    }
+}
+
+impl<C: Component> PartialOrd for BaseAlpha<C> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        self.0.partial_cmp(&other.0)
+    }
+}
+
+/// Last value written to component `C` by this crate.
+///
+/// If the component differs it was modified elsewhere, and its new value becomes the designed value.
+#[derive(Component)]
+pub struct Written<C: Component, V: Send + Sync + 'static = f32>(pub V, PhantomData<C>);
+
+impl<C: Component, V: Send + Sync + 'static> Written<C, V> {
+    pub fn new(value: V) -> Self {
+        Written(value, PhantomData)
+    }
+
+    /// Record `value` as written.
+    fn set(&mut self, value: V)
+    where
+        V: PartialEq,
+    {
+        if self.0 != value {
+            self.0 = value;
+        }
+    }
+}
+
+impl<C: Component, V: Send + Sync + Debug + 'static> Debug for Written<C, V> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("Written").field(&self.0).finish()
+    }
+}
+
+/// Last color written to an [`ImageNode`] with [`PremultipliedAlpha`].
+pub type WrittenColor = Written<BaseColor, Color>;
+
+/// A component with a single color whose designed alpha is recorded in [`BaseAlpha<Self>`].
+pub trait ColorComponent: Component {
+    fn alpha(&self) -> f32;
+    fn set_alpha(&mut self, alpha: f32);
+}
+
+impl ColorComponent for Sprite {
+    fn alpha(&self) -> f32 {
+        self.color.alpha()
+    }
 
-impl OpacityQuery for &mut Sprite {
+    fn set_alpha(&mut self, alpha: f32) {
+        self.color.set_alpha(alpha);
+    }
+}
+
+impl ColorComponent for ImageNode {
+    fn alpha(&self) -> f32 {
+        self.color.alpha()
+    }
+
+    fn set_alpha(&mut self, alpha: f32) {
+        self.color.set_alpha(alpha);
+    }
+}
+
+impl ColorComponent for BoxShadow {
+    fn alpha(&self) -> f32 {
+        self.color.alpha()
+    }
+
+    fn set_alpha(&mut self, alpha: f32) {
+        self.color.set_alpha(alpha);
+    }
+}
+
+impl ColorComponent for TextColor {
+    fn alpha(&self) -> f32 {
+        self.0.alpha()
+    }
+
+    fn set_alpha(&mut self, alpha: f32) {
+        self.0.set_alpha(alpha);
+    }
+}
+
+/// [`QueryData`] for a [`ColorComponent`] that respects [`BaseAlpha`].
+#[derive(Debug, QueryData)]
+#[query_data(mutable)]
+pub struct BaseAlphaQuery<C: ColorComponent> {
+    pub color: &'static mut C,
+    pub base: Option<&'static mut BaseAlpha<C>>,
+    pub written: Option<&'static mut Written<C>>,
+}
+
+impl<C: ColorComponent> OpacityQuery for BaseAlphaQuery<C> {
     type Cx = ();
 
     fn apply_opacity(this: &mut Self::Item<'_>, _: &mut (), opacity: f32) {
-        this.color.set_alpha(opacity);
+        let current = this.color.alpha();
+        if let (Some(base), Some(written)) = (&mut this.base, &this.written) {
+            if current != written.0 {
+                base.0 = current;
+            }
+        }
+        let alpha = this.base.as_ref().map(|x| x.0).unwrap_or(1.) * opacity;
+        if let Some(written) = &mut this.written {
+            written.set(alpha);
+        }
+        if current == alpha {
+            return;
+        }
+        this.color.set_alpha(alpha);
     }
 }
 
-impl OpacityQuery for &mut ImageNode {
+/// Designed color of an [`ImageNode`] with [`PremultipliedAlpha`],
+/// opacity is multiplied into all of its channels.
+///
+/// Recorded automatically the first time an entity is affected by opacity.
+/// If the color is modified elsewhere afterwards, it becomes the designed color.
+#[derive(Debug, Clone, Copy, PartialEq, Component)]
+pub struct BaseColor(pub LinearRgba);
+
+/// [`QueryData`] for [`ImageNode`], respects [`BaseAlpha`] or [`BaseColor`] with [`PremultipliedAlpha`].
+#[derive(Debug, QueryData)]
+#[query_data(mutable)]
+pub struct ImageNodeQuery {
+    pub image: &'static mut ImageNode,
+    pub base: Option<&'static mut BaseAlpha<ImageNode>>,
+    pub written: Option<&'static mut Written<ImageNode>>,
+    pub premultiplied: Option<&'static mut BaseColor>,
+    pub written_color: Option<&'static mut WrittenColor>,
+}
+
+impl OpacityQuery for ImageNodeQuery {
     type Cx = ();
 
     fn apply_opacity(this: &mut Self::Item<'_>, _: &mut (), opacity: f32) {
-        this.color.set_alpha(opacity);
+        let Some(base) = &mut this.premultiplied else {
+            let current = this.image.color.alpha();
+            if let (Some(base), Some(written)) = (&mut this.base, &this.written) {
+                if current != written.0 {
+                    base.0 = current;
+                }
+            }
+            let alpha = this.base.as_ref().map(|x| x.0).unwrap_or(1.) * opacity;
+            if let Some(written) = &mut this.written {
+                written.set(alpha);
+            }
+            if current == alpha {
+                return;
+            }
+            this.image.color.set_alpha(alpha);
+            return;
+        };
+        if let Some(written) = &this.written_color {
+            if this.image.color != written.0 {
+                base.0 = this.image.color.to_linear();
+            }
+        }
+        let color = Color::from(base.0 * opacity);
+        if let Some(written) = &mut this.written_color {
+            written.set(color);
+        }
+        if this.image.color == color {
+            return;
+        }
+        this.image.color = color;
     }
 }
 
-impl OpacityQuery for &mut TextColor {
+/// Opt-in component that fades the color of a [`BoxShadow`], respecting [`BaseAlpha<BoxShadow>`].
+///
+/// Shadows keep their own alpha by default, since [`BoxShadow`] is often
+/// part of a style shared by nodes that fade and nodes that do not.
+#[derive(Debug, Clone, Copy, Default, Component)]
+pub struct FadeBoxShadow;
+
+/// [`QueryData`] for a [`BoxShadow`] with [`FadeBoxShadow`].
+#[derive(Debug, QueryData)]
+#[query_data(mutable)]
+pub struct BoxShadowQuery {
+    pub shadow: BaseAlphaQuery<BoxShadow>,
+    pub marker: &'static FadeBoxShadow,
+}
+
+impl OpacityQuery for BoxShadowQuery {
     type Cx = ();
 
-    fn apply_opacity(this: &mut Self::Item<'_>, _: &mut (), opacity: f32) {
-        this.set_alpha(opacity);
+    fn apply_opacity(this: &mut Self::Item<'_>, cx: &mut (), opacity: f32) {
+        BaseAlphaQuery::apply_opacity(&mut this.shadow, cx, opacity);
+    }
+}
+
+pub fn record_box_shadow_base_alpha(
+    mut commands: Commands,
+    map: Res<OpacityMap>,
+    query: Query<(Entity, &BoxShadow, &FadeBoxShadow), Without<Written<BoxShadow>>>,
+) {
+    for (entity, shadow, _) in &query {
+        if map.contains(entity) {
+            commands
+                .entity(entity)
+                .insert(Written::<BoxShadow>::new(shadow.color.alpha()))
+                .insert_if_new(BaseAlpha::<BoxShadow>::new(shadow.color.alpha()));
+        }
+    }
+}
+
+pub fn record_base_alpha<C: ColorComponent>(
+    mut commands: Commands,
+    map: Res<OpacityMap>,
+    query: Query<(Entity, &C), Without<Written<C>>>,
+) {
+    for (entity, color) in &query {
+        if map.contains(entity) {
+            commands
+                .entity(entity)
+                .insert(Written::<C>::new(color.alpha()))
+                .insert_if_new(BaseAlpha::<C>::new(color.alpha()));
+        }
     }
 }
 
-/// Determine whether [`BorderColor`] and [`BackgroundColor`] are controlled by
-/// opacity or should stay transparent.
+/// Determine which of [`BorderColor`], [`BackgroundColor`] and [`Outline`] are controlled by opacity,
+/// the others keep their own alpha.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Component)]
 pub enum UiOpacity {
-    /// Both should stay transparent
+    /// Opacity controls none of them, border, background and outline colors are left untouched.
     #[default]
     None,
     /// Opacity controls border color.
@@ -49,45 +303,490 @@ pub enum UiOpacity {
     Background,
     /// Opacity controls border and background color.
     Both,
+    /// Opacity controls outline color, for example on outlined text, see [`OutlineBaseAlpha`].
+    Outline,
+    /// Opacity controls border, background and outline color.
+    All,
 }
 
+impl UiOpacity {
+    fn border(self) -> bool {
+        matches!(self, UiOpacity::Border | UiOpacity::Both | UiOpacity::All)
+    }
+
+    fn background(self) -> bool {
+        matches!(
+            self,
+            UiOpacity::Background | UiOpacity::Both | UiOpacity::All
+        )
+    }
+
+    fn outline(self) -> bool {
+        matches!(self, UiOpacity::Outline | UiOpacity::All)
+    }
+}
+
+/// Designed alpha of [`BackgroundColor`] and [`BorderColor`],
+/// opacity is multiplied into these values instead of replacing the alpha.
+///
+/// Recorded automatically the first time an entity with [`UiOpacity`] is affected by opacity.
+/// If either color is modified elsewhere afterwards, for example on hover,
+/// its new alpha becomes the designed alpha.
+#[derive(Debug, Clone, Copy, PartialEq, PartialOrd, Component)]
+pub struct UiBaseAlpha {
+    pub background: f32,
+    pub border: f32,
+}
+
+/// [`QueryData`] for [`BackgroundColor`] and [`BorderColor`] controlled by [`UiOpacity`].
+///
+/// [`BorderColor`] is a single color for all edges in this version of bevy,
+/// so every edge fades uniformly regardless of its width.
 #[derive(Debug, QueryData)]
 #[query_data(mutable)]
 pub struct UiColorQuery {
     pub ui_color: &'static UiOpacity,
     pub background: &'static mut BackgroundColor,
     pub border: &'static mut BorderColor,
+    pub base: Option<&'static mut UiBaseAlpha>,
+    pub written: Option<(
+        &'static mut Written<BackgroundColor>,
+        &'static mut Written<BorderColor>,
+    )>,
 }
 
 impl OpacityQuery for UiColorQuery {
     type Cx = ();
 
     fn apply_opacity(this: &mut Self::Item<'_>, _: &mut (), opacity: f32) {
-        match this.ui_color {
-            UiOpacity::None => (),
-            UiOpacity::Border => {
-                this.border.0.set_alpha(opacity);
+        let background = this.background.0.alpha();
+        let border = this.border.0.alpha();
+        if let (Some(base), Some((written_background, written_border))) =
+            (&mut this.base, &this.written)
+        {
+            if this.ui_color.background() && background != written_background.0 {
+                base.background = background;
+            }
+            if this.ui_color.border() && border != written_border.0 {
+                base.border = border;
+            }
+        }
+        let base = this.base.as_deref().copied().unwrap_or(UiBaseAlpha {
+            background: 1.,
+            border: 1.,
+        });
+        if this.ui_color.border() {
+            let alpha = base.border * opacity;
+            if let Some((_, written)) = &mut this.written {
+                written.set(alpha);
+            }
+            if border != alpha {
+                this.border.0.set_alpha(alpha);
+            }
+        }
+        if this.ui_color.background() {
+            let alpha = base.background * opacity;
+            if let Some((written, _)) = &mut this.written {
+                written.set(alpha);
+            }
+            if background != alpha {
+                this.background.0.set_alpha(alpha);
+            }
+        }
+    }
+}
+
+pub fn record_base_color(
+    mut commands: Commands,
+    map: Res<OpacityMap>,
+    query: Query<(Entity, &ImageNode, Has<PremultipliedAlpha>), Without<WrittenColor>>,
+) {
+    for (entity, image, premultiplied) in &query {
+        if premultiplied && map.contains(entity) {
+            commands
+                .entity(entity)
+                .insert(WrittenColor::new(image.color))
+                .insert_if_new(BaseColor(image.color.to_linear()));
+        }
+    }
+}
+
+pub fn record_ui_base_alpha(
+    mut commands: Commands,
+    map: Res<OpacityMap>,
+    query: Query<
+        (Entity, &UiOpacity, &BackgroundColor, &BorderColor),
+        Without<Written<BackgroundColor>>,
+    >,
+) {
+    for (entity, _, background, border) in &query {
+        if map.contains(entity) {
+            commands
+                .entity(entity)
+                .insert((
+                    Written::<BackgroundColor>::new(background.0.alpha()),
+                    Written::<BorderColor>::new(border.0.alpha()),
+                ))
+                .insert_if_new(UiBaseAlpha {
+                    background: background.0.alpha(),
+                    border: border.0.alpha(),
+                });
+        }
+    }
+}
+
+/// Designed alpha of an [`Outline`], opacity is multiplied into this value.
+///
+/// Kept apart from [`BaseAlpha<TextColor>`] since outlined text also has a [`TextColor`].
+/// Recorded automatically the first time an entity with [`UiOpacity::Outline`] or [`UiOpacity::All`]
+/// is affected by opacity. If the outline color is modified elsewhere afterwards,
+/// its new alpha becomes the designed alpha.
+#[derive(Debug, Clone, Copy, PartialEq, PartialOrd, Component)]
+pub struct OutlineBaseAlpha(pub f32);
+
+/// [`QueryData`] for [`Outline`] controlled by [`UiOpacity`].
+#[derive(Debug, QueryData)]
+#[query_data(mutable)]
+pub struct OutlineQuery {
+    pub ui_color: &'static UiOpacity,
+    pub outline: &'static mut Outline,
+    pub base: Option<&'static mut OutlineBaseAlpha>,
+    pub written: Option<&'static mut Written<Outline>>,
+}
+
+impl OpacityQuery for OutlineQuery {
+    type Cx = ();
+
+    fn apply_opacity(this: &mut Self::Item<'_>, _: &mut (), opacity: f32) {
+        if !this.ui_color.outline() {
+            return;
+        }
+        let current = this.outline.color.alpha();
+        if let (Some(base), Some(written)) = (&mut this.base, &this.written) {
+            if current != written.0 {
+                base.0 = current;
             }
-            UiOpacity::Background => {
-                this.background.0.set_alpha(opacity);
+        }
+        let alpha = this.base.as_ref().map(|x| x.0).unwrap_or(1.) * opacity;
+        if let Some(written) = &mut this.written {
+            written.set(alpha);
+        }
+        if current == alpha {
+            return;
+        }
+        this.outline.color.set_alpha(alpha);
+    }
+}
+
+pub fn record_outline_base_alpha(
+    mut commands: Commands,
+    map: Res<OpacityMap>,
+    query: Query<(Entity, &UiOpacity, &Outline), Without<Written<Outline>>>,
+) {
+    for (entity, ui_color, outline) in &query {
+        if ui_color.outline() && map.contains(entity) {
+            commands
+                .entity(entity)
+                .insert(Written::<Outline>::new(outline.color.alpha()))
+                .insert_if_new(OutlineBaseAlpha(outline.color.alpha()));
+        }
+    }
+}
+
+/// Opt-in component that fades the alpha of a camera's [`ClearColorConfig::Custom`],
+/// respecting [`BaseAlpha<Camera>`].
+///
+/// Only makes sense for cameras rendering to a transparent target, like a texture
+/// that is composited afterwards. Other clear color configs are not affected.
+#[derive(Debug, Clone, Copy, Default, Component)]
+pub struct FadeClearColor;
+
+#[derive(Debug, QueryData)]
+#[query_data(mutable)]
+pub struct ClearColorQuery {
+    pub camera: &'static mut Camera,
+    pub base: Option<&'static mut BaseAlpha<Camera>>,
+    pub written: Option<&'static mut Written<Camera>>,
+    pub marker: &'static FadeClearColor,
+}
+
+impl OpacityQuery for ClearColorQuery {
+    type Cx = ();
+
+    fn apply_opacity(this: &mut Self::Item<'_>, _: &mut (), opacity: f32) {
+        let ClearColorConfig::Custom(color) = &this.camera.clear_color else {
+            return;
+        };
+        let current = color.alpha();
+        if let (Some(base), Some(written)) = (&mut this.base, &this.written) {
+            if current != written.0 {
+                base.0 = current;
             }
-            UiOpacity::Both => {
-                this.border.0.set_alpha(opacity);
-                this.background.0.set_alpha(opacity);
+        }
+        let alpha = this.base.as_ref().map(|x| x.0).unwrap_or(1.) * opacity;
+        if let Some(written) = &mut this.written {
+            written.set(alpha);
+        }
+        if current == alpha {
+            return;
+        }
+        // Only dereference mutably when writing to avoid triggering change detection.
+        if let ClearColorConfig::Custom(color) = &mut this.camera.clear_color {
+            color.set_alpha(alpha);
+        }
+    }
+}
+
+pub fn record_clear_color_base_alpha(
+    mut commands: Commands,
+    map: Res<OpacityMap>,
+    query: Query<(Entity, &Camera, &FadeClearColor), Without<Written<Camera>>>,
+) {
+    for (entity, camera, _) in &query {
+        if let ClearColorConfig::Custom(color) = camera.clear_color {
+            if map.contains(entity) {
+                commands
+                    .entity(entity)
+                    .insert(Written::<Camera>::new(color.alpha()))
+                    .insert_if_new(BaseAlpha::<Camera>::new(color.alpha()));
             }
         }
     }
 }
 
-impl OpacityAsset for ColorMaterial {
-    fn apply_opacity(&mut self, opacity: f32) {
-        self.color.set_alpha(opacity)
+impl OpacityBaseMaterial for StandardMaterial {
+    fn base_alpha(&self) -> Option<f32> {
+        Some(self.base_color.alpha())
     }
+
+    fn apply_opacity(&mut self, base: Option<f32>, opacity: f32) {
+        self.base_color.set_alpha(base.unwrap_or(1.) * opacity);
+    }
+}
+
+/// Designed alpha of the base material of an [`ExtendedMaterial`].
+#[derive(Debug, Clone, Copy)]
+pub struct BaseMaterialAlpha {
+    alpha: f32,
+    /// Last alpha written by this crate, if the material differs it was modified elsewhere.
+    written: f32,
 }
 
-impl OpacityAsset for StandardMaterial {
-    fn apply_opacity(&mut self, opacity: f32) {
-        self.base_color.set_alpha(opacity)
+/// Designed alpha of base materials of every registered [`ExtendedMaterial`].
+#[derive(Debug, Default, Resource)]
+pub struct BaseMaterialAlphas(HashMap<UntypedAssetId, BaseMaterialAlpha>);
+
+/// Designed state of materials, shared by every schedule that applies opacity.
+#[derive(Debug, Resource)]
+pub struct MaterialBases<A: Asset, T>(pub HashMap<AssetId<A>, T>);
+
+impl<A: Asset, T> Default for MaterialBases<A, T> {
+    fn default() -> Self {
+        MaterialBases(HashMap::default())
+    }
+}
+
+/// Forget the designed state of materials that were removed or are no longer used.
+pub fn prune_material_bases<A: Asset, T: Send + Sync + 'static>(
+    mut events: EventReader<AssetEvent<A>>,
+    mut bases: ResMut<MaterialBases<A, T>>,
+) {
+    for event in events.read() {
+        if let AssetEvent::Removed { id } | AssetEvent::Unused { id } = event {
+            bases.0.remove(id);
+        }
+    }
+}
+
+/// Insert on an entity with a [`ColorMaterial`] or [`ImageNode`] whose texture has premultiplied alpha.
+///
+/// By default only the alpha of the color is scaled by opacity,
+/// which darkens premultiplied textures twice, since their color channels already include alpha.
+/// With this component all channels of the color are scaled instead, see [`BaseColor`].
+#[derive(Debug, Clone, Copy, Default, Component)]
+pub struct PremultipliedAlpha;
+
+/// [`QueryData`] for [`ColorMaterial`].
+///
+/// Opacity is multiplied into the alpha of the material when first encountered,
+/// or into all channels with [`PremultipliedAlpha`]. If the material is modified elsewhere
+/// its new color becomes the designed color.
+#[derive(Debug, QueryData)]
+pub struct ColorMaterialQuery {
+    pub material: &'static MeshMaterial2d<ColorMaterial>,
+    pub premultiplied: Has<PremultipliedAlpha>,
+}
+
+/// Designed color of a [`ColorMaterial`].
+#[derive(Debug, Clone, Copy)]
+pub struct ColorMaterialBase {
+    color: LinearRgba,
+    /// Last color written by this crate, if the material differs it was modified elsewhere.
+    written: Color,
+}
+
+impl OpacityQuery for ColorMaterialQuery {
+    type Cx = (
+        ResMut<'static, Assets<ColorMaterial>>,
+        ResMut<'static, MaterialBases<ColorMaterial, ColorMaterialBase>>,
+    );
+
+    fn apply_opacity(
+        this: &mut Self::Item<'_>,
+        (assets, bases): &mut <Self::Cx as SystemParam>::Item<'_, '_>,
+        opacity: f32,
+    ) {
+        #[cfg(feature = "debug")]
+        crate::debug::warn_weak_handle(&this.material.0, assets);
+        let id = this.material.id();
+        let Some(mat) = assets.get(id) else {
+            return;
+        };
+        let base = bases.0.entry(id).or_insert(ColorMaterialBase {
+            color: mat.color.to_linear(),
+            written: mat.color,
+        });
+        if mat.color != base.written {
+            base.color = mat.color.to_linear();
+            base.written = mat.color;
+        }
+        let mut color = mat.color;
+        if this.premultiplied {
+            color = (base.color * opacity).into();
+        } else {
+            // Only touch alpha to keep the color space of the material.
+            color.set_alpha(base.color.alpha * opacity);
+        }
+        if mat.color == color {
+            return;
+        }
+        let Some(mat) = assets.get_mut(id) else {
+            return;
+        };
+        mat.color = color;
+        base.written = color;
+    }
+}
+
+/// Designed state of a [`StandardMaterial`].
+#[derive(Debug, Clone, Copy)]
+pub struct StandardMaterialBase {
+    alpha: f32,
+    mask: Option<f32>,
+    /// Last alpha and alpha mode written by this crate, if the material differs it was modified elsewhere.
+    written: (f32, AlphaMode),
+    /// If any entity using the material has opacity below `1.0` this frame.
+    faded: bool,
+}
+
+impl StandardMaterialBase {
+    fn new(material: &StandardMaterial, faded: bool) -> Self {
+        StandardMaterialBase {
+            alpha: material.base_color.alpha(),
+            mask: None,
+            written: (material.base_color.alpha(), material.alpha_mode),
+            faded,
+        }
+    }
+}
+
+/// Mark [`StandardMaterial`]s used by any entity with opacity below `1.0`,
+/// so the alpha mode of a shared material does not depend on which entity applied it last.
+pub fn mark_faded_materials(
+    map: Res<OpacityMap>,
+    skip_hidden: Option<Res<SkipHidden>>,
+    assets: Res<Assets<StandardMaterial>>,
+    mut bases: ResMut<MaterialBases<StandardMaterial, StandardMaterialBase>>,
+    query: Query<(
+        Entity,
+        &MeshMaterial3d<StandardMaterial>,
+        Option<&InheritedVisibility>,
+    )>,
+) {
+    for base in bases.0.values_mut() {
+        base.faded = false;
+    }
+    for (entity, material, visibility) in &query {
+        if !map.get(entity).is_some_and(|x| x < 1.) {
+            continue;
+        }
+        if skip_hidden.is_some() && visibility.is_some_and(|x| !x.get()) {
+            continue;
+        }
+        let Some(mat) = assets.get(material.id()) else {
+            continue;
+        };
+        bases
+            .0
+            .entry(material.id())
+            .or_insert(StandardMaterialBase::new(mat, true))
+            .faded = true;
+    }
+}
+
+/// [`QueryData`] for [`StandardMaterial`].
+///
+/// Opacity is multiplied into the alpha of the material when first encountered.
+/// If the material is modified elsewhere its new alpha becomes the designed alpha.
+///
+/// Since alpha below the cutoff of [`AlphaMode::Mask`] makes the material vanish,
+/// masked materials are switched to [`AlphaMode::Blend`] while any entity using them
+/// has opacity below `1.0` and [`AlphaMode::Mask`] is restored once all of them return to `1.0`.
+///
+/// Materials that are not loaded yet are skipped, since opacity is applied every frame
+/// they are picked up as soon as they load.
+#[derive(Debug, QueryData)]
+pub struct StandardMaterialQuery {
+    pub material: &'static MeshMaterial3d<StandardMaterial>,
+}
+
+impl OpacityQuery for StandardMaterialQuery {
+    type Cx = (
+        ResMut<'static, Assets<StandardMaterial>>,
+        ResMut<'static, MaterialBases<StandardMaterial, StandardMaterialBase>>,
+    );
+
+    fn apply_opacity(
+        this: &mut Self::Item<'_>,
+        (assets, bases): &mut <Self::Cx as SystemParam>::Item<'_, '_>,
+        opacity: f32,
+    ) {
+        #[cfg(feature = "debug")]
+        crate::debug::warn_weak_handle(&this.material.0, assets);
+        let id = this.material.id();
+        let Some(mat) = assets.get(id) else {
+            return;
+        };
+        let base = bases
+            .0
+            .entry(id)
+            .or_insert(StandardMaterialBase::new(mat, opacity < 1.));
+        if mat.base_color.alpha() != base.written.0 {
+            base.alpha = mat.base_color.alpha();
+        }
+        if mat.alpha_mode != base.written.1 {
+            base.mask = None;
+        }
+        base.written = (mat.base_color.alpha(), mat.alpha_mode);
+        let mut alpha_mode = mat.alpha_mode;
+        if base.faded {
+            if let AlphaMode::Mask(cutoff) = alpha_mode {
+                base.mask = Some(cutoff);
+                alpha_mode = AlphaMode::Blend;
+            }
+        } else if let Some(cutoff) = base.mask.take() {
+            alpha_mode = AlphaMode::Mask(cutoff);
+        }
+        let alpha = base.alpha * opacity;
+        if mat.base_color.alpha() == alpha && mat.alpha_mode == alpha_mode {
+            return;
+        }
+        let Some(mat) = assets.get_mut(id) else {
+            return;
+        };
+        mat.alpha_mode = alpha_mode;
+        mat.base_color.set_alpha(alpha);
+        base.written = (alpha, alpha_mode);
     }
 }
 
@@ -95,16 +794,16 @@ impl<T> OpacityQuery for &MeshMaterial2d<T>
 where
     T: OpacityAsset + Material2d,
 {
-    type Cx = ResMut<'static, Assets<T>>;
+    type Cx = (ResMut<'static, Assets<T>>, AppliedAssets<'static, T>);
 
     fn apply_opacity(
         this: &mut Self::Item<'_>,
         cx: &mut <Self::Cx as SystemParam>::Item<'_, '_>,
         opacity: f32,
     ) {
-        if let Some(mat) = cx.get_mut(this.id()) {
-            mat.apply_opacity(opacity);
-        }
+        #[cfg(feature = "debug")]
+        crate::debug::warn_weak_handle(&this.0, &cx.0);
+        apply_asset_opacity(cx, this.id(), opacity)
     }
 }
 
@@ -112,15 +811,79 @@ impl<T> OpacityQuery for &MeshMaterial3d<T>
 where
     T: OpacityAsset + Material,
 {
-    type Cx = ResMut<'static, Assets<T>>;
+    type Cx = (ResMut<'static, Assets<T>>, AppliedAssets<'static, T>);
 
     fn apply_opacity(
         this: &mut Self::Item<'_>,
         cx: &mut <Self::Cx as SystemParam>::Item<'_, '_>,
         opacity: f32,
     ) {
-        if let Some(mat) = cx.get_mut(this.id()) {
-            mat.apply_opacity(opacity);
+        #[cfg(feature = "debug")]
+        crate::debug::warn_weak_handle(&this.0, &cx.0);
+        apply_asset_opacity(cx, this.id(), opacity)
+    }
+}
+
+impl<A, T> OpacityQuery for &MeshMaterial3d<ExtendedMaterial<A, T>>
+where
+    A: Material,
+    T: MaterialExtension + OpacityMaterialExtension<A>,
+{
+    type Cx = (
+        ResMut<'static, Assets<ExtendedMaterial<A, T>>>,
+        AppliedAssets<'static, ExtendedMaterial<A, T>>,
+        ResMut<'static, BaseMaterialAlphas>,
+    );
+
+    fn apply_opacity(
+        this: &mut Self::Item<'_>,
+        (assets, applied, bases): &mut <Self::Cx as SystemParam>::Item<'_, '_>,
+        opacity: f32,
+    ) {
+        #[cfg(feature = "debug")]
+        crate::debug::warn_weak_handle(&this.0, assets);
+        let id = this.id();
+        if !applied.insert(id, opacity) {
+            return;
+        }
+        let Some(mat) = assets.get(id) else {
+            return;
+        };
+        let base = T::base_alpha(&mat.base).map(|current| {
+            let base = bases.0.entry(id.untyped()).or_insert(BaseMaterialAlpha {
+                alpha: current,
+                written: current,
+            });
+            if current != base.written {
+                base.alpha = current;
+            }
+            base.alpha
+        });
+        let Some(mat) = assets.get_mut(id) else {
+            return;
+        };
+        T::apply_opacity(&mut mat.base, base, &mut mat.extension, opacity);
+        if let (Some(written), Some(base)) =
+            (T::base_alpha(&mat.base), bases.0.get_mut(&id.untyped()))
+        {
+            base.written = written;
         }
     }
 }
+
+impl<T> OpacityQuery for &MaterialNode<T>
+where
+    T: OpacityAsset + UiMaterial,
+{
+    type Cx = (ResMut<'static, Assets<T>>, AppliedAssets<'static, T>);
+
+    fn apply_opacity(
+        this: &mut Self::Item<'_>,
+        cx: &mut <Self::Cx as SystemParam>::Item<'_, '_>,
+        opacity: f32,
+    ) {
+        #[cfg(feature = "debug")]
+        crate::debug::warn_weak_handle(&this.0, &cx.0);
+        apply_asset_opacity(cx, this.id(), opacity)
+    }
+}
@@ -0,0 +1,106 @@
+use bevy::{
+    ecs::system::SystemParam,
+    log::warn_once,
+    prelude::{Entity, Parent, Query, With, World},
+};
+
+use crate::{Opacity, OpacityDisabled, OpacityFloor, OpacityOverride, SecondaryOpacity};
+
+/// Opacity related components of a single entity.
+struct Node {
+    own: f32,
+    pinned: Option<f32>,
+    floor: Option<f32>,
+}
+
+/// Walk up from `entity` with `get`, which returns the [`Node`] and parent of an entity,
+/// then combine the collected ancestors from the top down.
+///
+/// Stops at an entity with [`OpacityOverride`] or at the first repeated entity of a cycle.
+fn inherit(entity: Entity, get: impl Fn(Entity) -> Option<(Node, Option<Entity>)>) -> f32 {
+    let mut chain: Vec<(Entity, Node)> = Vec::new();
+    let mut current = Some(entity);
+    while let Some(entity) = current.take() {
+        if chain.iter().any(|(x, _)| *x == entity) {
+            warn_once!(
+                "Cycle detected in the hierarchy of entity {entity}, opacity may be incorrect."
+            );
+            break;
+        }
+        let Some((node, parent)) = get(entity) else {
+            break;
+        };
+        if node.pinned.is_none() {
+            current = parent;
+        }
+        chain.push((entity, node));
+    }
+    chain.iter().rev().fold(1., |value, (_, node)| {
+        let value = node.pinned.unwrap_or(value) * node.own;
+        match node.floor {
+            Some(floor) => value.max(floor),
+            None => value,
+        }
+    })
+}
+
+/// Compute the opacity of an entity by walking its ancestors.
+///
+/// Unlike [`OpacityMap`](crate::OpacityMap) this does not depend on opacity being
+/// calculated this frame. Returns `1.0` if no ancestor has [`struct@Opacity`].
+pub fn calculate_inherited_opacity(world: &World, entity: Entity) -> f32 {
+    inherit(entity, |entity| {
+        let entity = world.get_entity(entity).ok()?;
+        let own = if entity.contains::<OpacityDisabled>() {
+            1.
+        } else {
+            entity.get::<Opacity>().map(|x| x.0).unwrap_or(1.)
+                * entity.get::<SecondaryOpacity>().map(|x| x.0).unwrap_or(1.)
+        };
+        let node = Node {
+            own,
+            pinned: entity.get::<OpacityOverride>().map(|x| x.0),
+            floor: entity.get::<OpacityFloor>().map(|x| x.0),
+        };
+        Some((node, entity.get::<Parent>().map(|x| x.get())))
+    })
+}
+
+/// [`SystemParam`] version of [`calculate_inherited_opacity`].
+#[derive(SystemParam)]
+pub struct InheritedOpacity<'w, 's> {
+    parents: Query<'w, 's, &'static Parent>,
+    opacity: Query<
+        'w,
+        's,
+        (
+            Option<&'static Opacity>,
+            Option<&'static SecondaryOpacity>,
+            Option<&'static OpacityOverride>,
+        ),
+    >,
+    floors: Query<'w, 's, &'static OpacityFloor>,
+    disabled: Query<'w, 's, (), With<OpacityDisabled>>,
+}
+
+impl InheritedOpacity<'_, '_> {
+    /// Compute the opacity of an entity by walking its ancestors.
+    ///
+    /// Returns `1.0` if no ancestor has [`struct@Opacity`].
+    pub fn get(&self, entity: Entity) -> f32 {
+        inherit(entity, |entity| {
+            let (opacity, secondary, pinned) = self.opacity.get(entity).ok()?;
+            let own = if self.disabled.contains(entity) {
+                1.
+            } else {
+                opacity.map(|x| x.0).unwrap_or(1.) * secondary.map(|x| x.0).unwrap_or(1.)
+            };
+            let node = Node {
+                own,
+                pinned: pinned.map(|x| x.0),
+                floor: self.floors.get(entity).ok().map(|x| x.0),
+            };
+            Some((node, self.parents.get(entity).ok().map(|x| x.get())))
+        })
+    }
+}
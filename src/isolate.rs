@@ -0,0 +1,86 @@
+use bevy::{
+    asset::{Assets, Handle},
+    pbr::{MeshMaterial3d, StandardMaterial},
+    prelude::{Commands, Component, Entity, Parent, Query, Res, ResMut, With},
+};
+
+use crate::OpacityMap;
+
+/// Insert on an entity with [`Opacity`](struct@crate::Opacity) to give it and descendants sharing
+/// a [`StandardMaterial`] their own copy while they are faded.
+///
+/// Works on nested entities as well as on roots, descendants are isolated if any ancestor
+/// up to their opacity root has this component.
+///
+/// The copy is created when a descendant's opacity drops below `1.0`,
+/// and the shared material is restored once it returns to `1.0`.
+#[derive(Debug, Clone, Copy, Default, Component)]
+pub struct AutoIsolateMaterial;
+
+/// The shared material of an entity isolated by [`AutoIsolateMaterial`].
+///
+/// Removed when the shared material is restored, dropping the copy along with its recorded designed state.
+#[derive(Debug, Clone, Component)]
+pub struct IsolatedMaterial {
+    pub original: Handle<StandardMaterial>,
+}
+
+pub fn isolate_materials(
+    mut commands: Commands,
+    map: Res<OpacityMap>,
+    mut assets: ResMut<Assets<StandardMaterial>>,
+    markers: Query<(), With<AutoIsolateMaterial>>,
+    parents: Query<&Parent>,
+    mut query: Query<(
+        Entity,
+        &mut MeshMaterial3d<StandardMaterial>,
+        Option<&IsolatedMaterial>,
+    )>,
+) {
+    for (entity, mut material, isolated) in &mut query {
+        let Some(opacity) = map.get(entity) else {
+            continue;
+        };
+        match isolated {
+            None if opacity < 1. => {
+                if !is_marked(entity, &map, &markers, &parents) {
+                    continue;
+                }
+                let Some(copy) = assets.get(&material.0).cloned() else {
+                    continue;
+                };
+                let original = std::mem::replace(&mut material.0, assets.add(copy));
+                commands
+                    .entity(entity)
+                    .insert(IsolatedMaterial { original });
+            }
+            Some(isolated) if opacity >= 1. => {
+                material.0 = isolated.original.clone();
+                commands.entity(entity).remove::<IsolatedMaterial>();
+            }
+            _ => (),
+        }
+    }
+}
+
+/// Returns `true` if the entity or an ancestor up to its opacity root has [`AutoIsolateMaterial`].
+fn is_marked(
+    mut entity: Entity,
+    map: &OpacityMap,
+    markers: &Query<(), With<AutoIsolateMaterial>>,
+    parents: &Query<&Parent>,
+) -> bool {
+    let root = map.root_of(entity);
+    loop {
+        if markers.contains(entity) {
+            return true;
+        }
+        if Some(entity) == root {
+            return false;
+        }
+        let Ok(parent) = parents.get(entity) else {
+            return false;
+        };
+        entity = parent.get();
+    }
+}
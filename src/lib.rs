@@ -7,73 +7,326 @@
 //! this does not need to be put on every entity in the tree.
 //! Entities with no `Opacity` ancestor will not not affected by this crate.
 //!
+//! Opacity is recalculated every frame, so descendants spawned later, like entities of
+//! an asynchronously loaded `SceneRoot`, receive the correct opacity on their first frame
+//! as long as they are spawned before [`OpacitySet::Calculate`].
+//! The same applies to components like `Sprite` inserted on existing descendants.
+//! Call [`apply_opacity_now`] to apply opacity outside of `PostUpdate`.
+//!
+//! To combine two independent effects on one entity, add a [`SecondaryOpacity`],
+//! which is multiplied with `Opacity`.
+//!
+//! Add an [`OpacityFloor`] to keep an entity visible, computed opacity never goes below it.
+//!
+//! Add an [`OpacityDisabled`] to temporarily treat an entity's own opacity as `1.0`
+//! while keeping its value, see [`OpacityCommands::set_opacity_enabled`].
+//!
 //! # Support for native types
 //!
 //! We innately support `2d`, `3d` and `ui`, this includes `Sprite`, `TextColor`, `StandardMaterial`,
-//! `ColorMaterial`, `Image`, `BackgroundColor` and `ForegroundColor`.
+//! `ColorMaterial`, `Image`, `BackgroundColor`, `ForegroundColor` and `BoxShadow` with [`FadeBoxShadow`].
+//!
+//! `TextColor` covers both UI `Text` and world space `Text2d`, including `TextSpan` children.
+//! An `Outline` on a UI node, including text, fades along with it with [`UiOpacity::Outline`] or [`UiOpacity::All`].
+//! Insert [`FadeClearColor`] on a camera rendering to a transparent target to fade its custom clear color.
+//!
+//! Sliced and tiled `Sprite`s and `ImageNode`s are rendered with their single color,
+//! so borders and center fade uniformly.
+//! `ImageNode` opacity scales the alpha of its color, which multiplies the sampled texture.
+//! Textures with premultiplied alpha get darkened twice this way, insert [`PremultipliedAlpha`]
+//! on these to scale every channel instead.
+//!
+//! Opacity is multiplied into the designed alpha of these types instead of replacing it,
+//! see [`BaseAlpha`] and [`UiBaseAlpha`]. For materials, the alpha is recorded per asset.
+//!
+//! `PointLight`, `SpotLight` and `DirectionalLight` with [`FadeLight`] are dimmed by opacity,
+//! see [`BaseIntensity`].
 //!
 //! Additionally you can implement [`OpacityQuery`] or derive `Opacity` to make your own types
 //! and materials work with this crate. Combining `OpacityQuery` with custom `QueryData` can
-//! add support for third party types.
+//! add support for third party types. Materials that implement `Reflect` can skip this with
+//! [`OpacityExtension::register_opacity_material3d_reflect`], which sets their first color field.
 //!
 //! # [`FadeIn`] and [`FadeOut`]
 //!
 //! These components adds a quick way to add and remove entities from your scenes smoothly.
 //! You should add a [`FadeIn`] during the `spawn` call and use `entity.insert(FadeOut)` instead
-//! of `entity.despawn_recursive()`
+//! of `entity.despawn_recursive()`, or use [`OpacityCommands::fade_out_subtree`].
+//! Spawn [`FadeInBundle`] instead of `FadeIn` to always start from `0.0`.
+//! Use `with_ease` to fade with any of bevy's `EaseFunction`s.
+//!
+//! [`FadeCompleted`] is sent when either finishes, for sequencing.
+//! Query [`FadeState`] to check whether an entity is currently fading.
+//!
+//! # [`Blink`]
+//!
+//! For hit feedback, [`Blink`] toggles opacity a number of times before returning to `1.0`.
+//!
+//! # [`Toast`]
+//!
+//! For notifications, [`Toast`] fades in, stays visible for a while, then fades out and despawns.
+//!
+//! For particle bursts, [`OpacityCommands::spawn_fading_group`] spawns a root with children
+//! that fade out and despawn together.
+//!
+//! # [`ScreenFade`]
+//!
+//! Insert [`ScreenFade::to_black`] or [`ScreenFade::from_black`] on a camera
+//! for full-screen transitions.
+//!
+//! # [`SceneFade`]
+//!
+//! Insert [`SceneFade`] on the root of a 3d scene to dim lights outside of its hierarchy
+//! along with it. Lights inside the hierarchy dim with [`FadeLight`].
+//! Ambient and environment map lighting cannot be faded per entity.
+//!
+//! # [`OpacityTrack`]
+//!
+//! For more than two points, [`OpacityTrack`] animates opacity through a list of keyframes.
+//!
+//! # [`DistanceFade`]
+//!
+//! For distant impostors, [`DistanceFade`] fades an entity out as it moves away from the camera.
+//!
+//! # [`OpacityFollow`]
+//!
+//! To follow a target that changes often, [`OpacityFollow`] moves opacity with a spring
+//! that keeps its velocity when retargeted.
+//!
+//! # Render world
+//!
+//! Opacity is calculated and applied in the main world during `PostUpdate`.
+//! For colors driven in the render world, insert [`RenderOpacity`] and read it during extraction.
+//!
+//! # Features
+//!
+//! * `animation`: Implements `Animatable` for [`struct@Opacity`].
+//! * `async`: Adds [`OpacityEntityCommands`] that return an awaitable [`FadeToken`].
+//! * `audio`: Adds [`OpacityExtension::register_opacity_audio`] for fading audio volume.
+//! * `debug`: Warns when an entity with [`struct@Opacity`] does not affect anything,
+//!   fades a `StandardMaterial` with `AlphaMode::Opaque` or a material with a dropped weak handle.
+//!   Adds [`OpacityDebugPlugin`] for logging computed opacity.
+//! * `gizmos`: Adds [`OpacityExtension::register_opacity_gizmos`] for fading `ShowAabbGizmo` colors.
+//! * `state`: Adds [`FadeOut::then_set_state`] for changing `States` after a fade out.
+//! * `wireframe`: Adds [`OpacityExtension::register_opacity_wireframe`] for fading `WireframeColor`.
 //!
 //! # FAQ
 //!
 //! * My 3d scene is not fading correctly
 //!
-//!  Ensure materials are duplicated and unique, since we write to the underlying material directly.
+//!  Ensure materials are duplicated and unique, since we write to the underlying material directly,
+//!  or insert [`AutoIsolateMaterial`] on the faded entity to duplicate `StandardMaterial`s while fading.
 //!  Also make sure `AlphaMode` is set to `Blend` if applicable.
+//!  `StandardMaterial`s using `AlphaMode::Mask` are temporarily switched to `Blend`
+//!  while fading and restored afterwards.
+//!  The `debug` feature warns about faded materials with `AlphaMode::Opaque`.
+//!  Material handles must be strong, an asset only referenced by weak handles may be dropped
+//!  and silently stops fading. The `debug` feature warns about weak handles to missing assets.
+//!
+//! * How do I fade a tilemap?
+//!
+//!  Tilemap crates render chunks with their own `Material2d`, implement [`OpacityAsset`] for it,
+//!  for example by setting the alpha of the color in its uniform, and call
+//!  [`OpacityExtension::register_opacity_material2d`].
+//!  Putting [`struct@Opacity`] on the layer then fades every chunk
+//!  spawned as its descendant.
+//!
+//! * How do I fade buttons with hover colors?
+//!
+//!  Change `BackgroundColor` on `Interaction` in `Update` as usual and insert [`UiOpacity::Background`],
+//!  opacity is applied in `PostUpdate` and multiplied into the new color's alpha,
+//!  which becomes the designed alpha in [`UiBaseAlpha`].
 
 mod alpha;
+#[cfg(feature = "animation")]
+mod animation;
+#[cfg(feature = "audio")]
+mod audio;
+#[cfg(feature = "debug")]
+mod debug;
+mod distance;
+mod extract;
 mod fading;
+mod follow;
+#[cfg(feature = "gizmos")]
+mod gizmos;
+mod handle;
 mod impls;
+mod inherited;
+mod isolate;
+mod light;
+mod reader;
+mod reflect;
+mod screen;
+mod threshold;
+#[cfg(feature = "async")]
+mod token;
+mod track;
+#[cfg(feature = "wireframe")]
+mod wireframe;
 #[doc(hidden)]
 pub use alpha::set_alpha;
 #[doc(hidden)]
-pub use bevy::asset::{Assets, Handle};
+pub use bevy::app::{App as BevyApp, Plugin as BevyPlugin};
 #[doc(hidden)]
-pub use bevy::ecs::query::WorldQuery;
+pub use bevy::asset::{Asset, Assets, Handle};
+#[doc(hidden)]
+pub use bevy::ecs::{change_detection::DetectChangesMut, component::Component, query::WorldQuery};
 
+pub use alpha::OpacityAlpha;
+#[cfg(feature = "audio")]
+pub use audio::BaseVolume;
+use bevy::render::view::{InheritedVisibility, VisibilityPlugin};
 use bevy::sprite::Material2d;
 use bevy::{
-    app::{App, Plugin, PostUpdate},
-    asset::Asset,
+    app::{App, FixedUpdate, Plugin, PostUpdate},
+    asset::{AssetEvent, AssetId},
     ecs::{
-        entity::EntityHashMap,
+        entity::{EntityHashMap, EntityHashSet},
         query::QueryData,
+        schedule::{common_conditions::not, ScheduleLabel},
         system::{StaticSystemParam, SystemParam},
     },
-    pbr::{ExtendedMaterial, Material, MaterialExtension, MeshMaterial3d, StandardMaterial},
+    log::warn_once,
+    pbr::{DirectionalLight, Material, MeshMaterial3d, PointLight, SpotLight, StandardMaterial},
     prelude::ImageNode,
     prelude::{
-        Children, Component, Entity, IntoSystemConfigs, IntoSystemSetConfigs, Query, Res, ResMut,
-        Resource, SystemSet,
+        Children, Entity, IntoSystemConfigs, IntoSystemSetConfigs, Local, Query, Res, ResMut,
+        Resource, SystemSet, With, World,
     },
+    reflect::Reflect,
     sprite::{ColorMaterial, MeshMaterial2d, Sprite},
     text::TextColor,
+    time::{Fixed, Virtual},
     transform::systems::{propagate_transforms, sync_simple_transforms},
+    ui::{MaterialNode, UiMaterial, UiSystem},
+};
+#[cfg(feature = "debug")]
+pub use debug::OpacityDebugPlugin;
+use distance::distance_fade;
+pub use distance::DistanceFade;
+pub use extract::RenderOpacity;
+use fading::{blink, fade_in, fade_out, toast};
+pub use fading::{
+    reset_fade, Blink, DespawnMode, FadeCompleted, FadeConfig, FadeCurve, FadeIn, FadeInBundle,
+    FadeOut, FadeOutThen, FadeState, FadeStateItem, OpacityCommands, Toast, ToastPhase,
+};
+use follow::opacity_follow;
+pub use follow::OpacityFollow;
+pub use impls::{
+    BaseAlpha, BaseColor, FadeBoxShadow, FadeClearColor, OutlineBaseAlpha, PremultipliedAlpha,
+    UiBaseAlpha, UiOpacity,
 };
-use fading::{fade_in, fade_out};
-pub use fading::{FadeIn, FadeOut};
-pub use impls::UiOpacity;
+pub use inherited::{calculate_inherited_opacity, InheritedOpacity};
+use isolate::isolate_materials;
+pub use isolate::{AutoIsolateMaterial, IsolatedMaterial};
+use light::{record_base_intensity, scene_fade, BaseIntensityQuery, SceneFadeLights};
+pub use light::{BaseIntensity, FadeLight, SceneFade};
+pub use reader::OpacityReader;
+use reflect::ReflectMaterialPlugin;
+use screen::screen_fade;
+pub use screen::{ScreenFade, ScreenFadeOverlay};
 use std::marker::PhantomData;
+use threshold::opacity_thresholds;
+pub use threshold::{OpacityThresholds, ThresholdCrossed};
+#[cfg(feature = "async")]
+pub use token::{FadeToken, OpacityEntityCommands};
+use track::opacity_track;
+pub use track::{OpacityTrack, TrackEnd, TrackInterpolation};
 
 #[cfg(feature = "derive")]
 pub use bevy_mod_opacity_derive::Opacity;
-use impls::UiColorQuery;
+use handle::OpacityAssetQuery;
+use impls::{
+    mark_faded_materials, prune_material_bases, record_base_alpha, record_base_color,
+    record_box_shadow_base_alpha, record_clear_color_base_alpha, record_outline_base_alpha,
+    record_ui_base_alpha, BaseAlphaQuery, BaseMaterialAlphas, BoxShadowQuery, ClearColorQuery,
+    ColorMaterialBase, ColorMaterialQuery, ImageNodeQuery, MaterialBases, OutlineQuery,
+    StandardMaterialBase, StandardMaterialQuery, UiColorQuery,
+};
 
 /// [`Component`] of opacity of this entity and its children.
-#[derive(Debug, Clone, Copy, Component, PartialEq, PartialOrd)]
+///
+/// Opacity is not clamped, values outside of `0.0..=1.0` are passed to
+/// [`OpacityQuery::apply_opacity`] as is. Native colors will clamp alpha when rendering,
+/// but custom implementations can use the extra range, for example to scale emissive for HDR.
+#[derive(Debug, Clone, Copy, Component, PartialEq, PartialOrd, Reflect)]
 pub struct Opacity(pub f32);
 
 impl Opacity {
     pub const INVISIBLE: Opacity = Opacity(0.);
-    pub const FULL: Opacity = Opacity(1.);
+    /// Fully visible, opacity `1.0`.
+    pub const OPAQUE: Opacity = Opacity(1.);
+    /// Alias of [`Opacity::OPAQUE`].
+    ///
+    /// ```
+    /// # use bevy_mod_opacity::Opacity;
+    /// assert_eq!(Opacity::FULL, Opacity::OPAQUE);
+    /// ```
+    pub const FULL: Opacity = Opacity::OPAQUE;
+
+    /// Linearly interpolate between `a` and `b`, with `t` clamped to `0..=1`.
+    ///
+    /// ```
+    /// # use bevy_mod_opacity::Opacity;
+    /// assert_eq!(Opacity::lerp(0.2, 1.0, 0.5), 0.6);
+    /// assert_eq!(Opacity::lerp(0.2, 1.0, 2.0), 1.0);
+    /// ```
+    pub fn lerp(a: f32, b: f32, t: f32) -> f32 {
+        Self::lerp_unclamped(a, b, t.clamp(0., 1.))
+    }
+
+    /// Linearly interpolate between `a` and `b`, extrapolating if `t` is outside of `0..=1`.
+    ///
+    /// This is the math used by fading curves, which may overshoot.
+    pub fn lerp_unclamped(a: f32, b: f32, t: f32) -> f32 {
+        a + (b - a) * t
+    }
+
+    /// Set opacity to `value` clamped to `0..=1`, for custom drivers in [`OpacitySet::PreFade`].
+    ///
+    /// Only the value changes, running fades keep running and are not reset.
+    /// A [`FadeIn`] or [`FadeOut`] without a curve continues from this value at the same speed
+    /// and still despawns once it reaches its target. Insert a new fade to restart it instead.
+    ///
+    /// Unlike assigning to [`struct@Opacity`] directly the value is clamped,
+    /// since fades move within `0..=1` and a value outside of it would complete them immediately.
+    ///
+    /// ```
+    /// # use bevy_mod_opacity::Opacity;
+    /// let mut opacity = Opacity::OPAQUE;
+    /// opacity.set_current(-1.0);
+    /// assert_eq!(opacity, Opacity::INVISIBLE);
+    /// ```
+    pub fn set_current(&mut self, value: f32) {
+        self.0 = value.clamp(0., 1.);
+    }
+
+    /// Set opacity to `1.0`, see [`reset_fade`] for recycling pooled entities.
+    pub fn reset_opaque(&mut self) {
+        *self = Opacity::OPAQUE;
+    }
+}
+
+/// Create an [`struct@Opacity`] from its value.
+///
+/// ```
+/// # use bevy_mod_opacity::Opacity;
+/// assert_eq!(Opacity::from(0.5), Opacity(0.5));
+/// let opacity: Opacity = 0.25.into();
+/// assert_eq!(f32::from(opacity), 0.25);
+/// ```
+impl From<f32> for Opacity {
+    fn from(value: f32) -> Self {
+        Opacity(value)
+    }
+}
+
+/// Returns the current value, since [`struct@Opacity`] has no target.
+impl From<Opacity> for f32 {
+    fn from(value: Opacity) -> Self {
+        value.0
+    }
 }
 
 /// # Why default `1.0`
@@ -85,25 +338,121 @@ impl Default for Opacity {
     }
 }
 
+/// Pin the opacity of this entity and its children, ignoring opacity of ancestors.
+///
+/// The entity's own [`struct@Opacity`] still applies on top of this value.
+#[derive(Debug, Clone, Copy, Component, PartialEq, PartialOrd)]
+#[require(Opacity)]
+pub struct OpacityOverride(pub f32);
+
+/// A second opacity of this entity and its children, multiplied with [`struct@Opacity`].
+///
+/// Lets two effects drive opacity independently, for example a health based fade
+/// on [`struct@Opacity`] and a hit flash on this. Built-in drivers like [`FadeIn`]
+/// only modify [`struct@Opacity`].
+#[derive(Debug, Clone, Copy, Component, PartialEq, PartialOrd)]
+#[require(Opacity)]
+pub struct SecondaryOpacity(pub f32);
+
+impl Default for SecondaryOpacity {
+    fn default() -> Self {
+        Self(1.0)
+    }
+}
+
+/// A lower bound on the computed opacity of this entity and its children.
+///
+/// Keeps important elements visible, for example a fade out stops at this value
+/// instead of `0.0`. Descendants still multiply their own [`struct@Opacity`] on top.
+///
+/// A [`FadeOut`] on an entity with this component does not despawn it.
+#[derive(Debug, Clone, Copy, Component, Default, PartialEq, PartialOrd)]
+#[require(Opacity)]
+pub struct OpacityFloor(pub f32);
+
+/// Disable the [`struct@Opacity`] and [`SecondaryOpacity`] of this entity,
+/// they count as `1.0` for it and its children until this is removed.
+///
+/// The stored values are kept, so fades resume from where they were when re-enabled.
+#[derive(Debug, Clone, Copy, Component, Default, PartialEq, Eq)]
+#[require(Opacity)]
+pub struct OpacityDisabled;
+
 /// A map of entity to opacity, if not present, the entity does not have an opacity root node.
 /// This means the entity is out of the scope of this crate and should not be handled.
 #[derive(Debug, Resource, Default)]
-pub struct OpacityMap(EntityHashMap<f32>);
+pub struct OpacityMap(EntityHashMap<OpacityData>);
+
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct OpacityData {
+    value: f32,
+    root: Entity,
+}
+
+impl OpacityMap {
+    /// Returns the computed opacity of an entity.
+    pub fn get(&self, entity: Entity) -> Option<f32> {
+        self.0.get(&entity).map(|x| x.value)
+    }
+
+    /// Returns the outermost entity with [`struct@Opacity`] that controls this entity,
+    /// or the closest [`OpacityOverride`] ancestor.
+    pub fn root_of(&self, entity: Entity) -> Option<Entity> {
+        self.0.get(&entity).map(|x| x.root)
+    }
+
+    /// Returns `true` if the entity is its own [`OpacityMap::root_of`],
+    /// nested entities with [`struct@Opacity`] are not roots unless they have an [`OpacityOverride`].
+    pub fn is_root(&self, entity: Entity) -> bool {
+        self.root_of(entity) == Some(entity)
+    }
+
+    /// Returns `true` if the entity is affected by opacity.
+    pub fn contains(&self, entity: Entity) -> bool {
+        self.0.contains_key(&entity)
+    }
+}
 
 /// [`SystemSet`] of opacity,
 /// runs in [`PostUpdate`] between transform propagation and visibility calculation.
+///
+/// [`OpacitySet::Apply`] runs after [`UiSystem::Layout`], so UI nodes laid out this frame
+/// are rendered with the correct opacity. It is not ordered with [`UiSystem::PostLayout`]
+/// and [`UiSystem::Stack`], which do not read colors.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, SystemSet)]
 pub enum OpacitySet {
+    /// Slot for user systems that modify [`struct@Opacity`] before built-in fading.
+    PreFade,
+    /// Built-in drivers like [`FadeIn`], [`FadeOut`], [`Blink`], [`Toast`], [`OpacityTrack`],
+    /// [`OpacityFollow`], [`DistanceFade`] and [`ScreenFade`].
     Fading,
+    /// Slot for user systems that read or override the result of fading.
     PostFade,
+    /// Propagates opacity through the hierarchy into [`OpacityMap`].
     Calculate,
+    /// Writes opacity to registered [`OpacityQuery`]s.
     Apply,
 }
 
 /// A [`QueryData`] with an opacity value.
+///
+/// Tuples of up to 4 `OpacityQuery`s apply all of them in one pass, matching entities
+/// that satisfy every member. Their contexts must not conflict, for example by mutably
+/// accessing the same assets.
 pub trait OpacityQuery: QueryData + Send + Sync {
+    /// Context of [`OpacityQuery::apply_opacity`], use `()` if not needed.
+    ///
+    /// Use a tuple like `(ResMut<'static, Assets<T>>, Res<'static, Config>)`
+    /// or a `#[derive(SystemParam)]` struct for multiple parameters.
+    ///
+    /// A `Local` is not shared with [`apply_opacity_now`], keep persistent state in a resource.
     type Cx: SystemParam;
 
+    /// Write opacity to the item.
+    ///
+    /// Compare before writing where possible, mutably accessing components or assets
+    /// marks them as changed even if the value stays the same.
+    /// Nothing is returned, since the caller cannot undo a change mark made here.
     fn apply_opacity(
         this: &mut Self::Item<'_>,
         cx: &mut <Self::Cx as SystemParam>::Item<'_, '_>,
@@ -112,84 +461,428 @@ pub trait OpacityQuery: QueryData + Send + Sync {
 }
 
 /// An [`Asset`] with an opacity value.
+///
+/// An asset shared by many entities is written once per frame for each distinct opacity.
 pub trait OpacityAsset: Asset {
     fn apply_opacity(&mut self, opacity: f32);
 }
 
-/// A [`MaterialExtension`] with an opacity value.
-pub trait OpacityMaterialExtension<A> {
-    fn apply_opacity(a: &mut A, b: &mut Self, opacity: f32);
+/// A type whose fields are set by opacity, implemented by `#[derive(Opacity)]`.
+///
+/// Fields marked `#[opacity(nested)]` forward opacity through this trait.
+pub trait OpacityNested {
+    /// Set alpha of all fields, returns `true` if any of them was changed.
+    fn apply_opacity(&mut self, opacity: f32) -> bool;
+}
+
+/// The base material of an [`ExtendedMaterial`](bevy::pbr::ExtendedMaterial) whose extension uses `#[opacity(extends = ..)]`.
+///
+/// Implemented for every [`OpacityAsset`] and for [`StandardMaterial`].
+/// Like the built-in [`StandardMaterial`] query, opacity is multiplied into the designed alpha
+/// of a [`StandardMaterial`] base, which is recorded per asset.
+pub trait OpacityBaseMaterial {
+    /// Alpha that opacity is multiplied into, `None` if opacity is written to fields instead.
+    ///
+    /// Recorded the first time the material is faded, and again if the material
+    /// was modified elsewhere since opacity was last written.
+    fn base_alpha(&self) -> Option<f32> {
+        None
+    }
+
+    /// Write opacity, `base` is the recorded [`OpacityBaseMaterial::base_alpha`].
+    fn apply_opacity(&mut self, base: Option<f32>, opacity: f32);
 }
 
-impl<A: Material, T: MaterialExtension> OpacityAsset for ExtendedMaterial<A, T>
-where
-    T: OpacityMaterialExtension<A>,
-{
-    fn apply_opacity(&mut self, opacity: f32) {
-        OpacityMaterialExtension::apply_opacity(&mut self.base, &mut self.extension, opacity);
+impl<T: OpacityAsset> OpacityBaseMaterial for T {
+    fn apply_opacity(&mut self, _: Option<f32>, opacity: f32) {
+        OpacityAsset::apply_opacity(self, opacity);
     }
 }
 
+/// A [`MaterialExtension`](bevy::pbr::MaterialExtension) with an opacity value.
+pub trait OpacityMaterialExtension<A> {
+    /// Alpha of the base material that opacity is multiplied into,
+    /// see [`OpacityBaseMaterial::base_alpha`].
+    fn base_alpha(_: &A) -> Option<f32> {
+        None
+    }
+
+    /// Write opacity, `base` is the recorded [`OpacityMaterialExtension::base_alpha`].
+    fn apply_opacity(a: &mut A, base: Option<f32>, b: &mut Self, opacity: f32);
+}
+
+macro_rules! impl_opacity_query_tuple {
+    ($(($T: ident, $i: tt)),*) => {
+        impl<$($T: OpacityQuery),*> OpacityQuery for ($($T,)*) {
+            type Cx = ($($T::Cx,)*);
+
+            fn apply_opacity(
+                this: &mut Self::Item<'_>,
+                cx: &mut <Self::Cx as SystemParam>::Item<'_, '_>,
+                opacity: f32,
+            ) {
+                $($T::apply_opacity(&mut this.$i, &mut cx.$i, opacity);)*
+            }
+        }
+    };
+}
+
+impl_opacity_query_tuple!((A, 0), (B, 1));
+impl_opacity_query_tuple!((A, 0), (B, 1), (C, 2));
+impl_opacity_query_tuple!((A, 0), (B, 1), (C, 2), (D, 3));
+
+/// Reuses the stack across frames, so this is allocation free in steady state.
+///
+/// Entities visited twice in one traversal indicate a cycle in the hierarchy
+/// and are skipped with a warning.
 fn calculate_opacity(
     mut map: ResMut<OpacityMap>,
-    query: Query<(Entity, &Opacity)>,
+    mut stack: Local<Vec<(Entity, OpacityData)>>,
+    mut visited: Local<EntityHashSet>,
+    query: Query<(
+        Entity,
+        &Opacity,
+        Option<&SecondaryOpacity>,
+        Option<&OpacityOverride>,
+    )>,
+    floors: Query<&OpacityFloor>,
+    disabled: Query<(), With<OpacityDisabled>>,
     children: Query<&Children>,
 ) {
+    let own = |entity: Entity, opacity: &Opacity, secondary: Option<&SecondaryOpacity>| {
+        if disabled.contains(entity) {
+            return 1.;
+        }
+        opacity.0 * secondary.map(|x| x.0).unwrap_or(1.)
+    };
     map.0.clear();
-    let mut stack = Vec::new();
-    for (entity, opacity) in &query {
+    stack.clear();
+    for (entity, opacity, secondary, pinned) in &query {
         if map.0.contains_key(&entity) {
             continue;
         }
-        stack.push((entity, opacity.0));
-        while let Some((entity, opacity)) = stack.pop() {
-            map.0.insert(entity, opacity);
+        stack.push((
+            entity,
+            OpacityData {
+                value: pinned.map(|x| x.0).unwrap_or(1.) * own(entity, opacity, secondary),
+                root: entity,
+            },
+        ));
+        visited.clear();
+        while let Some((entity, data)) = stack.pop() {
+            if !visited.insert(entity) {
+                warn_once!(
+                    "Cycle detected in the hierarchy of entity {entity}, opacity may be incorrect."
+                );
+                continue;
+            }
+            let data = match floors.get(entity) {
+                Ok(floor) => OpacityData {
+                    value: data.value.max(floor.0),
+                    ..data
+                },
+                Err(_) => data,
+            };
+            map.0.insert(entity, data);
             if let Ok(children) = children.get(entity) {
                 for entity in children.iter().copied() {
-                    let op = query.get(entity).map(|(_, x)| x.0).unwrap_or(1.);
-                    stack.push((entity, opacity * op));
+                    let data = match query.get(entity) {
+                        Ok((_, op, secondary, Some(pinned))) => OpacityData {
+                            value: pinned.0 * own(entity, op, secondary),
+                            root: entity,
+                        },
+                        Ok((_, op, secondary, None)) => OpacityData {
+                            value: data.value * own(entity, op, secondary),
+                            root: data.root,
+                        },
+                        Err(_) => data,
+                    };
+                    stack.push((entity, data));
                 }
             }
         }
     }
 }
 
+/// Distance at which smoothed opacity snaps to the calculated value.
+const SMOOTHING_EPSILON: f32 = 1e-3;
+
+/// Smooth [`OpacityMap`] if [`OpacitySettings::smoothing`] is set.
+fn smooth_opacity(
+    mut map: ResMut<OpacityMap>,
+    settings: Res<OpacitySettings>,
+    mut previous: Local<EntityHashMap<f32>>,
+) {
+    let Some(factor) = settings.smoothing else {
+        previous.clear();
+        return;
+    };
+    let factor = factor.clamp(0., 1.);
+    previous.retain(|entity, _| map.0.contains_key(entity));
+    for (entity, data) in map.0.iter_mut() {
+        let value = match previous.get(entity) {
+            Some(prev) => Opacity::lerp(*prev, data.value, factor),
+            None => data.value,
+        };
+        if (value - data.value).abs() >= SMOOTHING_EPSILON {
+            data.value = value;
+        }
+        previous.insert(*entity, data.value);
+    }
+}
+
 /// Add support for writing opacity to a [`QueryData`].
+///
+/// Registering the same [`QueryData`] again does nothing.
 #[derive(Debug)]
 pub(crate) struct OpacityQueryPlugin<C: OpacityQuery>(PhantomData<C>);
 
 impl<C: OpacityQuery + 'static> Plugin for OpacityQueryPlugin<C> {
     fn build(&self, app: &mut App) {
-        app.add_systems(
-            PostUpdate,
-            apply_opacity_query::<C>.in_set(OpacitySet::Apply),
-        );
+        if app.is_plugin_added::<Self>() {
+            return;
+        }
+        add_opacity_systems(app, || apply_opacity_query::<C>.in_set(OpacitySet::Apply));
+    }
+
+    fn is_unique(&self) -> bool {
+        false
     }
 }
 
+/// Schedule run by [`apply_opacity_now`], containing the systems of
+/// [`OpacitySet::Calculate`] and [`OpacitySet::Apply`] added by this crate.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, ScheduleLabel)]
+struct ApplyOpacityNow;
+
+/// Add systems to [`PostUpdate`] and to the schedule run by [`apply_opacity_now`].
+fn add_opacity_systems<M, S: IntoSystemConfigs<M>>(app: &mut App, systems: impl Fn() -> S) {
+    app.add_systems(PostUpdate, systems());
+    app.add_systems(ApplyOpacityNow, systems());
+}
+
+/// Calculate and apply opacity immediately instead of waiting for [`PostUpdate`],
+/// for example right after spawning an invisible entity in `Last`.
+///
+/// This applies to every entity, not only recently spawned ones.
+/// Drivers like [`FadeIn`] and [`OpacitySettings::smoothing`] are not advanced.
+pub fn apply_opacity_now(world: &mut World) {
+    let _ = world.try_run_schedule(ApplyOpacityNow);
+}
+
+/// Present if [`InheritedVisibility`] is computed, in which case hidden entities are skipped.
+#[derive(Debug, Resource)]
+struct SkipHidden;
+
 fn apply_opacity_query<Q: OpacityQuery>(
     map: Res<OpacityMap>,
+    skip_hidden: Option<Res<SkipHidden>>,
     cx: StaticSystemParam<Q::Cx>,
-    mut query: Query<(Entity, Q)>,
+    mut query: Query<(Entity, Q, Option<&InheritedVisibility>)>,
+    #[cfg(feature = "debug")] mut commands: bevy::prelude::Commands,
+    #[cfg(feature = "debug")] affected: Query<(), With<debug::OpacityAffected>>,
 ) {
     let mut cx = cx.into_inner();
-    for (entity, mut component) in &mut query {
-        if let Some(opacity) = map.0.get(&entity) {
-            Q::apply_opacity(&mut component, &mut cx, *opacity);
+    for (entity, mut component, visibility) in &mut query {
+        let Some(opacity) = map.get(entity) else {
+            continue;
+        };
+        #[cfg(feature = "debug")]
+        if !affected.contains(entity) {
+            commands.entity(entity).try_insert(debug::OpacityAffected);
         }
+        if skip_hidden.is_some() && visibility.is_some_and(|x| !x.get()) {
+            continue;
+        }
+        Q::apply_opacity(&mut component, &mut cx, opacity);
     }
 }
 
+/// Generate a [`Plugin`] that registers a material, as an alternative to calling
+/// [`OpacityExtension::register_opacity_material2d`],
+/// [`OpacityExtension::register_opacity_material3d`] or
+/// [`OpacityExtension::register_opacity_ui_material`].
+///
+/// Unlike `#[opacity(asset)]` which only implements [`OpacityAsset`],
+/// this registers the material to the [`App`] when the plugin is added.
+///
+/// ```
+/// # use bevy::{prelude::*, reflect::TypePath, render::render_resource::AsBindGroup, sprite::Material2d};
+/// # use bevy_mod_opacity::{impl_opacity_material, Opacity};
+/// #[derive(Debug, Clone, TypePath, Asset, AsBindGroup, Opacity)]
+/// #[opacity(asset)]
+/// pub struct MyMaterial {
+///     #[opacity]
+///     pub color: Srgba,
+/// }
+///
+/// impl Material2d for MyMaterial {}
+///
+/// impl_opacity_material!(pub MyMaterialOpacityPlugin, MyMaterial, 2d);
+///
+/// App::new().add_plugins(MyMaterialOpacityPlugin);
+/// ```
+#[macro_export]
+macro_rules! impl_opacity_material {
+    ($vis: vis $plugin: ident, $ty: ty, 2d) => {
+        $vis struct $plugin;
+
+        impl $crate::BevyPlugin for $plugin {
+            fn build(&self, app: &mut $crate::BevyApp) {
+                $crate::OpacityExtension::register_opacity_material2d::<$ty>(app);
+            }
+        }
+    };
+    ($vis: vis $plugin: ident, $ty: ty, 3d) => {
+        $vis struct $plugin;
+
+        impl $crate::BevyPlugin for $plugin {
+            fn build(&self, app: &mut $crate::BevyApp) {
+                $crate::OpacityExtension::register_opacity_material3d::<$ty>(app);
+            }
+        }
+    };
+    ($vis: vis $plugin: ident, $ty: ty, ui) => {
+        $vis struct $plugin;
+
+        impl $crate::BevyPlugin for $plugin {
+            fn build(&self, app: &mut $crate::BevyApp) {
+                $crate::OpacityExtension::register_opacity_ui_material::<$ty>(app);
+            }
+        }
+    };
+}
+
 /// Plugin for [`bevy_mod_opacity`](crate) that adds support for basic bevy types.
+///
+/// Can be added more than once, for example by third party plugins,
+/// only the first instance is built. Configure it with [`OpacitySettings`],
+/// or add the plugin returned by [`OpacityPlugin::builder`].
+#[derive(Debug, Clone, Copy, Default)]
 pub struct OpacityPlugin;
 
+impl OpacityPlugin {
+    /// Returns a plugin that inserts [`OpacitySettings`] and adds [`OpacityPlugin`].
+    ///
+    /// ```
+    /// # use bevy::prelude::*;
+    /// # use bevy_mod_opacity::OpacityPlugin;
+    /// App::new().add_plugins(OpacityPlugin::builder().fixed_interpolation(true));
+    /// ```
+    pub const fn builder() -> OpacityPluginBuilder {
+        OpacityPluginBuilder {
+            settings: OpacitySettings {
+                fixed: false,
+                smoothing: None,
+            },
+        }
+    }
+}
+
+/// Plugin that inserts [`OpacitySettings`] and adds [`OpacityPlugin`], see [`OpacityPlugin::builder`].
+///
+/// The settings are inserted even if [`OpacityPlugin`] was already added, for example by a third party plugin.
+#[derive(Debug, Clone, Copy)]
+pub struct OpacityPluginBuilder {
+    settings: OpacitySettings,
+}
+
+impl OpacityPluginBuilder {
+    /// Set [`OpacitySettings::fixed`].
+    pub const fn fixed_interpolation(mut self, fixed: bool) -> Self {
+        self.settings.fixed = fixed;
+        self
+    }
+
+    /// Set [`OpacitySettings::smoothing`].
+    pub const fn with_smoothing(mut self, smoothing: f32) -> Self {
+        self.settings.smoothing = Some(smoothing);
+        self
+    }
+}
+
+impl Plugin for OpacityPluginBuilder {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(self.settings);
+        app.add_plugins(OpacityPlugin);
+    }
+
+    fn is_unique(&self) -> bool {
+        false
+    }
+}
+
+/// Settings of [`OpacityPlugin`], insert or modify this resource to change them.
+///
+/// ```
+/// # use bevy::prelude::*;
+/// # use bevy_mod_opacity::{OpacityPlugin, OpacitySettings};
+/// App::new()
+///     .insert_resource(OpacitySettings {
+///         fixed: true,
+///         smoothing: Some(0.5),
+///     })
+///     .add_plugins(OpacityPlugin);
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Resource)]
+pub struct OpacitySettings {
+    /// If `true`, run built-in drivers like [`FadeIn`] and [`FadeOut`] in `FixedUpdate`
+    /// using `Time<Fixed>`, making opacity part of the deterministic simulation.
+    ///
+    /// [`OpacitySet::PreFade`], [`OpacitySet::Fading`] and [`OpacitySet::PostFade`]
+    /// are ordered in `FixedUpdate` as well, calculating and applying opacity stays in `PostUpdate`.
+    /// [`DistanceFade`] and [`ScreenFade`] always run in `PostUpdate`, since they read
+    /// transforms and cameras of the rendered frame.
+    pub fixed: bool,
+    /// Move applied opacity towards the calculated value by this factor every frame,
+    /// hiding visible steps at low framerates. The factor is clamped to `0..=1`.
+    ///
+    /// [`OpacityMap`] contains the smoothed value, which snaps to the calculated value
+    /// once close enough, so static opacity is always reached exactly.
+    pub smoothing: Option<f32>,
+}
+
+fn fixed_drivers(settings: Res<OpacitySettings>) -> bool {
+    settings.fixed
+}
+
 pub trait OpacityExtension {
     fn register_opacity<Q: OpacityQuery + 'static>(&mut self) -> &mut Self;
     fn register_opacity_component<C: Component>(&mut self) -> &mut Self
     where
         &'static mut C: OpacityQuery;
     fn register_opacity_material2d<M: Material2d + OpacityAsset>(&mut self) -> &mut Self;
-    fn register_opacity_material3d<M: Material + OpacityAsset>(&mut self) -> &mut Self;
+    /// Register a [`Material`] used by `MeshMaterial3d`.
+    ///
+    /// Also registers an `ExtendedMaterial` whose extension uses `#[derive(Opacity)]` with
+    /// `#[opacity(extends = ..)]` or `#[opacity(masks = ..)]`.
+    fn register_opacity_material3d<M: Material>(&mut self) -> &mut Self
+    where
+        &'static MeshMaterial3d<M>: OpacityQuery;
+    /// Register a [`UiMaterial`] used by `MaterialNode`.
+    fn register_opacity_ui_material<M: UiMaterial + OpacityAsset>(&mut self) -> &mut Self;
+    /// Register a [`Material`] without implementing [`OpacityAsset`], setting the alpha
+    /// of its first `Color`, `LinearRgba` or `Srgba` field through reflection.
+    ///
+    /// Only top level fields of a struct are considered, opacity is multiplied into the designed
+    /// alpha of the field. Reflection is slower than [`OpacityExtension::register_opacity_material3d`].
+    fn register_opacity_material3d_reflect<M: Material + Reflect>(&mut self) -> &mut Self;
+    /// Register an [`OpacityAsset`] referenced by component `C`,
+    /// like `MeshMaterial2d` or `MeshMaterial3d` for materials.
+    fn register_opacity_asset<A: OpacityAsset, C: Component>(&mut self) -> &mut Self
+    where
+        for<'t> &'t C: Into<AssetId<A>>;
+    /// Scale the volume of `AudioSink` and `SpatialAudioSink` by opacity, see [`BaseVolume`].
+    #[cfg(feature = "audio")]
+    fn register_opacity_audio(&mut self) -> &mut Self;
+    /// Scale the color of `ShowAabbGizmo` by opacity, respecting its own [`BaseAlpha`].
+    ///
+    /// Gizmos without a color use their config group's color and are not affected.
+    #[cfg(feature = "gizmos")]
+    fn register_opacity_gizmos(&mut self) -> &mut Self;
+    /// Scale the color of `WireframeColor` by opacity, respecting its own [`BaseAlpha`].
+    #[cfg(feature = "wireframe")]
+    fn register_opacity_wireframe(&mut self) -> &mut Self;
 }
 
 impl OpacityExtension for App {
@@ -211,33 +904,196 @@ impl OpacityExtension for App {
         self
     }
 
-    fn register_opacity_material3d<M: Material + OpacityAsset>(&mut self) -> &mut Self {
+    fn register_opacity_material3d<M: Material>(&mut self) -> &mut Self
+    where
+        &'static MeshMaterial3d<M>: OpacityQuery,
+    {
         self.add_plugins(OpacityQueryPlugin::<&MeshMaterial3d<M>>(PhantomData));
         self
     }
+
+    fn register_opacity_ui_material<M: UiMaterial + OpacityAsset>(&mut self) -> &mut Self {
+        self.add_plugins(OpacityQueryPlugin::<&MaterialNode<M>>(PhantomData));
+        self
+    }
+
+    fn register_opacity_material3d_reflect<M: Material + Reflect>(&mut self) -> &mut Self {
+        self.add_plugins(ReflectMaterialPlugin::<M>(PhantomData));
+        self
+    }
+
+    fn register_opacity_asset<A: OpacityAsset, C: Component>(&mut self) -> &mut Self
+    where
+        for<'t> &'t C: Into<AssetId<A>>,
+    {
+        self.add_plugins(OpacityQueryPlugin::<OpacityAssetQuery<A, C>>(PhantomData));
+        self
+    }
+
+    #[cfg(feature = "audio")]
+    fn register_opacity_audio(&mut self) -> &mut Self {
+        self.add_plugins(audio::AudioOpacityPlugin);
+        self
+    }
+
+    #[cfg(feature = "gizmos")]
+    fn register_opacity_gizmos(&mut self) -> &mut Self {
+        self.add_plugins(gizmos::GizmosOpacityPlugin);
+        self
+    }
+
+    #[cfg(feature = "wireframe")]
+    fn register_opacity_wireframe(&mut self) -> &mut Self {
+        self.add_plugins(wireframe::WireframeOpacityPlugin);
+        self
+    }
 }
 
 impl Plugin for OpacityPlugin {
     fn build(&self, app: &mut App) {
-        use bevy::render::view::VisibilitySystems::*;
         use OpacitySet::*;
+        if app.is_plugin_added::<Self>() {
+            return;
+        }
         app.init_resource::<OpacityMap>();
+        app.init_resource::<OpacitySettings>();
+        app.init_resource::<FadeConfig>();
+        app.init_resource::<MaterialBases<ColorMaterial, ColorMaterialBase>>();
+        app.init_resource::<MaterialBases<StandardMaterial, StandardMaterialBase>>();
+        app.init_resource::<BaseMaterialAlphas>();
+        app.init_resource::<SceneFadeLights>();
+        app.add_event::<AssetEvent<ColorMaterial>>();
+        app.add_event::<AssetEvent<StandardMaterial>>();
+        app.add_event::<ThresholdCrossed>();
+        app.add_event::<FadeCompleted>();
         app.configure_sets(
             PostUpdate,
-            (Fading, PostFade, Calculate, Apply)
+            (PreFade, Fading, PostFade, Calculate, Apply)
                 .chain()
                 .after(propagate_transforms)
-                .after(sync_simple_transforms)
-                .before(CheckVisibility)
-                .before(UpdateFrusta),
+                .after(sync_simple_transforms),
+        );
+        app.configure_sets(ApplyOpacityNow, (Calculate, Apply).chain());
+        app.configure_sets(PostUpdate, Apply.after(UiSystem::Layout));
+        // Before the drivers, so an overlay starts fading this frame.
+        app.add_systems(
+            PostUpdate,
+            screen_fade
+                .before(fade_in::<Virtual>)
+                .before(fade_out::<Virtual>)
+                .in_set(Fading),
+        );
+        app.add_systems(PostUpdate, distance_fade.in_set(Fading));
+        app.configure_sets(FixedUpdate, (PreFade, Fading, PostFade).chain());
+        app.add_systems(
+            FixedUpdate,
+            (
+                fade_in::<Fixed>,
+                fade_out::<Fixed>,
+                blink::<Fixed>,
+                opacity_track::<Fixed>,
+                opacity_follow::<Fixed>,
+                toast::<Fixed>,
+            )
+                .run_if(fixed_drivers)
+                .in_set(Fading),
         );
-        app.add_systems(PostUpdate, (fade_in, fade_out).in_set(Fading));
-        app.add_systems(PostUpdate, calculate_opacity.in_set(Calculate));
-        app.register_opacity_component::<Sprite>();
-        app.register_opacity_component::<TextColor>();
-        app.register_opacity_component::<ImageNode>();
-        app.register_opacity_material2d::<ColorMaterial>();
-        app.register_opacity_material3d::<StandardMaterial>();
+        app.add_systems(
+            PostUpdate,
+            (
+                fade_in::<Virtual>,
+                fade_out::<Virtual>,
+                blink::<Virtual>,
+                opacity_track::<Virtual>,
+                opacity_follow::<Virtual>,
+                toast::<Virtual>,
+            )
+                .run_if(not(fixed_drivers))
+                .in_set(Fading),
+        );
+        add_opacity_systems(app, || {
+            (
+                calculate_opacity,
+                scene_fade,
+                (
+                    isolate_materials,
+                    record_base_alpha::<Sprite>,
+                    record_base_alpha::<ImageNode>,
+                    record_base_color,
+                    record_base_alpha::<TextColor>,
+                    record_box_shadow_base_alpha,
+                    record_ui_base_alpha,
+                    record_outline_base_alpha,
+                    record_clear_color_base_alpha,
+                    record_base_intensity::<PointLight>,
+                    record_base_intensity::<SpotLight>,
+                    record_base_intensity::<DirectionalLight>,
+                ),
+            )
+                .chain()
+                .in_set(Calculate)
+        });
+        app.add_systems(
+            PostUpdate,
+            smooth_opacity.after(scene_fade).in_set(Calculate),
+        );
+        #[cfg(feature = "debug")]
+        {
+            app.add_systems(PostUpdate, debug::warn_unsupported.after(Apply));
+            app.add_systems(PostUpdate, debug::warn_opaque_materials.after(Apply));
+        }
+        app.add_systems(PostUpdate, opacity_thresholds.in_set(Apply));
+        app.add_systems(
+            PostUpdate,
+            (
+                prune_material_bases::<ColorMaterial, ColorMaterialBase>,
+                prune_material_bases::<StandardMaterial, StandardMaterialBase>,
+            )
+                .in_set(Calculate),
+        );
+        #[cfg(feature = "async")]
+        {
+            app.init_resource::<token::PendingFades>();
+            app.add_systems(PostUpdate, token::resolve_fades.in_set(Apply));
+        }
+        app.register_opacity::<BaseAlphaQuery<Sprite>>();
+        app.register_opacity::<BaseAlphaQuery<TextColor>>();
+        app.register_opacity::<ImageNodeQuery>();
+        app.register_opacity::<BoxShadowQuery>();
+        app.register_opacity::<ColorMaterialQuery>();
+        app.register_opacity::<StandardMaterialQuery>();
+        add_opacity_systems(app, || {
+            mark_faded_materials
+                .in_set(Apply)
+                .before(apply_opacity_query::<StandardMaterialQuery>)
+        });
         app.register_opacity::<UiColorQuery>();
+        app.register_opacity::<OutlineQuery>();
+        app.register_opacity::<ClearColorQuery>();
+        app.register_opacity_component::<RenderOpacity>();
+        app.register_opacity::<BaseIntensityQuery<PointLight>>();
+        app.register_opacity::<BaseIntensityQuery<SpotLight>>();
+        app.register_opacity::<BaseIntensityQuery<DirectionalLight>>();
+    }
+
+    fn finish(&self, app: &mut App) {
+        // Visibility sets only exist with rendering, skip on headless apps.
+        if app.is_plugin_added::<VisibilityPlugin>()
+            && !app.world().contains_resource::<SkipHidden>()
+        {
+            use bevy::render::view::VisibilitySystems::*;
+            app.insert_resource(SkipHidden);
+            app.configure_sets(
+                PostUpdate,
+                OpacitySet::Apply
+                    .after(VisibilityPropagate)
+                    .before(CheckVisibility)
+                    .before(UpdateFrusta),
+            );
+        }
+    }
+
+    fn is_unique(&self) -> bool {
+        false
     }
 }
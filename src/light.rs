@@ -0,0 +1,222 @@
+use bevy::{
+    ecs::{
+        entity::EntityHashSet,
+        query::{Has, QueryData},
+    },
+    pbr::{DirectionalLight, PointLight, SpotLight},
+    prelude::{
+        Children, Commands, Component, Entity, Local, Query, Res, ResMut, Resource, Without,
+    },
+};
+
+use crate::{Opacity, OpacityData, OpacityMap, OpacityQuery};
+
+/// Designed intensity of an entity's [`PointLight`], [`SpotLight`] or the illuminance
+/// of a [`DirectionalLight`], opacity is multiplied into this value.
+///
+/// Recorded automatically the first time a light with [`FadeLight`] or one listed in a
+/// [`SceneFade`] is affected by opacity, modify this to change the designed intensity afterwards.
+#[derive(Debug, Clone, Copy, PartialEq, PartialOrd, Component)]
+pub struct BaseIntensity(pub f32);
+
+/// Opt-in component that dims a [`PointLight`], [`SpotLight`] or [`DirectionalLight`]
+/// by its computed opacity, respecting [`BaseIntensity`].
+///
+/// Lights keep their intensity by default, so fading a UI or a single model does not
+/// change the lighting of everything under the same root. Lights listed in a [`SceneFade`]
+/// are dimmed by the scene without this component.
+#[derive(Debug, Clone, Copy, Default, Component)]
+pub struct FadeLight;
+
+/// Lights currently dimmed by a [`SceneFade`].
+#[derive(Debug, Default, Resource)]
+pub struct SceneFadeLights(pub EntityHashSet);
+
+/// A light component whose designed intensity is recorded in [`BaseIntensity`].
+pub trait LightComponent: Component {
+    fn intensity(&self) -> f32;
+    fn set_intensity(&mut self, intensity: f32);
+}
+
+impl LightComponent for PointLight {
+    fn intensity(&self) -> f32 {
+        self.intensity
+    }
+
+    fn set_intensity(&mut self, intensity: f32) {
+        self.intensity = intensity;
+    }
+}
+
+impl LightComponent for SpotLight {
+    fn intensity(&self) -> f32 {
+        self.intensity
+    }
+
+    fn set_intensity(&mut self, intensity: f32) {
+        self.intensity = intensity;
+    }
+}
+
+impl LightComponent for DirectionalLight {
+    fn intensity(&self) -> f32 {
+        self.illuminance
+    }
+
+    fn set_intensity(&mut self, intensity: f32) {
+        self.illuminance = intensity;
+    }
+}
+
+/// [`QueryData`] for a [`LightComponent`] that respects [`BaseIntensity`].
+#[derive(Debug, QueryData)]
+#[query_data(mutable)]
+pub struct BaseIntensityQuery<L: LightComponent> {
+    pub entity: Entity,
+    pub light: &'static mut L,
+    pub base: Option<&'static BaseIntensity>,
+    pub fade: Has<FadeLight>,
+}
+
+impl<L: LightComponent> OpacityQuery for BaseIntensityQuery<L> {
+    type Cx = Res<'static, SceneFadeLights>;
+
+    fn apply_opacity(this: &mut Self::Item<'_>, scene: &mut Res<SceneFadeLights>, opacity: f32) {
+        if !this.fade && !scene.0.contains(&this.entity) {
+            return;
+        }
+        let Some(base) = this.base else {
+            return;
+        };
+        let intensity = base.0 * opacity;
+        if this.light.intensity() == intensity {
+            return;
+        }
+        this.light.set_intensity(intensity);
+    }
+}
+
+/// Any built-in light with a recorded [`BaseIntensity`].
+#[derive(Debug, QueryData)]
+#[query_data(mutable)]
+pub struct AnyLight {
+    pub base: &'static BaseIntensity,
+    pub fade: Has<FadeLight>,
+    pub point: Option<&'static mut PointLight>,
+    pub spot: Option<&'static mut SpotLight>,
+    pub directional: Option<&'static mut DirectionalLight>,
+}
+
+pub fn record_base_intensity<L: LightComponent>(
+    mut commands: Commands,
+    map: Res<OpacityMap>,
+    scene: Res<SceneFadeLights>,
+    query: Query<(Entity, &L, Has<FadeLight>), Without<BaseIntensity>>,
+) {
+    for (entity, light, fade) in &query {
+        if (fade || scene.0.contains(&entity)) && map.contains(entity) {
+            commands
+                .entity(entity)
+                .insert(BaseIntensity(light.intensity()));
+        }
+    }
+}
+
+/// Fade a 3d scene together with lights outside of its hierarchy.
+///
+/// Meshes that are descendants of the entity are already affected by its [`struct@Opacity`],
+/// as are descendant lights with [`FadeLight`]. This additionally multiplies the computed opacity
+/// of listed lights like a sun that are spawned separately, so lighting dims as the scene fades.
+/// The [`struct@Opacity`] of these lights is not modified and still applies on top.
+///
+/// Once the scene is despawned, this component is removed or a light is no longer listed,
+/// lights without [`FadeLight`] are restored to their [`BaseIntensity`].
+///
+/// # Limitations
+///
+/// `AmbientLight` and `EnvironmentMapLight` are global or per camera and cannot be
+/// faded per entity, modify their brightness or intensity alongside if needed.
+/// Shadows are not faded, but dim along with their light.
+#[derive(Debug, Clone, Default, Component)]
+#[require(Opacity)]
+pub struct SceneFade {
+    pub lights: Vec<Entity>,
+}
+
+impl SceneFade {
+    pub fn new(lights: impl IntoIterator<Item = Entity>) -> Self {
+        SceneFade {
+            lights: lights.into_iter().collect(),
+        }
+    }
+
+    /// Add a light to fade along with the scene.
+    pub fn with_light(mut self, light: Entity) -> Self {
+        self.lights.push(light);
+        self
+    }
+}
+
+pub fn scene_fade(
+    mut map: ResMut<OpacityMap>,
+    scenes: Query<(Entity, &SceneFade)>,
+    children: Query<&Children>,
+    mut lights: Query<AnyLight>,
+    mut stack: Local<Vec<Entity>>,
+    mut dimmed: ResMut<SceneFadeLights>,
+    // Lights dimmed in the previous frame.
+    mut previous: Local<EntityHashSet>,
+) {
+    let dimmed = &mut dimmed.0;
+    std::mem::swap(dimmed, &mut *previous);
+    dimmed.clear();
+    for (scene, fade) in &scenes {
+        let Some(opacity) = map.get(scene) else {
+            continue;
+        };
+        let root = map.root_of(scene);
+        for light in fade.lights.iter().copied() {
+            // Lights in the scene's own hierarchy are already faded.
+            if map.root_of(light).is_some_and(|x| Some(x) == root) {
+                continue;
+            }
+            stack.clear();
+            stack.push(light);
+            while let Some(entity) = stack.pop() {
+                dimmed.insert(entity);
+                map.0
+                    .entry(entity)
+                    .and_modify(|data| data.value *= opacity)
+                    .or_insert(OpacityData {
+                        value: opacity,
+                        root: scene,
+                    });
+                if let Ok(children) = children.get(entity) {
+                    stack.extend(children.iter().copied());
+                }
+            }
+        }
+    }
+    // Lights with their own opacity and `FadeLight` are written when opacity is applied.
+    for entity in previous.drain() {
+        if dimmed.contains(&entity) {
+            continue;
+        }
+        let Ok(mut light) = lights.get_mut(entity) else {
+            continue;
+        };
+        if light.fade && map.contains(entity) {
+            continue;
+        }
+        let intensity = light.base.0;
+        if let Some(light) = light.point.as_mut() {
+            light.set_intensity(intensity);
+        }
+        if let Some(light) = light.spot.as_mut() {
+            light.set_intensity(intensity);
+        }
+        if let Some(light) = light.directional.as_mut() {
+            light.set_intensity(intensity);
+        }
+    }
+}
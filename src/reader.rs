@@ -0,0 +1,39 @@
+use bevy::{
+    ecs::system::SystemParam,
+    prelude::{Entity, Query, Res},
+};
+
+use crate::{FadeState, OpacityMap};
+
+/// Read only access to computed opacity and running fades.
+///
+/// Values are from [`OpacitySet::Calculate`](crate::OpacitySet::Calculate),
+/// run after it for the current frame.
+#[derive(SystemParam)]
+pub struct OpacityReader<'w, 's> {
+    map: Res<'w, OpacityMap>,
+    fades: Query<'w, 's, FadeState>,
+}
+
+impl OpacityReader<'_, '_> {
+    /// Returns the computed opacity of an entity, see [`OpacityMap::get`].
+    pub fn opacity_of(&self, entity: Entity) -> Option<f32> {
+        self.map.get(entity)
+    }
+
+    /// Returns the root that controls this entity, see [`OpacityMap::root_of`].
+    pub fn root_of(&self, entity: Entity) -> Option<Entity> {
+        self.map.root_of(entity)
+    }
+
+    /// Returns `true` if a built-in driver is animating the entity or its root,
+    /// see [`FadeState::is_animating`](crate::FadeStateItem::is_animating).
+    pub fn is_fading(&self, entity: Entity) -> bool {
+        let fading = |entity| {
+            self.fades
+                .get(entity)
+                .is_ok_and(|state| state.is_animating())
+        };
+        fading(entity) || self.root_of(entity).is_some_and(fading)
+    }
+}
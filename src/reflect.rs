@@ -0,0 +1,151 @@
+use std::marker::PhantomData;
+
+use bevy::{
+    app::{App, Plugin, PostUpdate},
+    asset::{AssetEvent, Assets},
+    color::{Alpha, Color, LinearRgba, Srgba},
+    ecs::{query::QueryData, system::SystemParamItem},
+    log::warn,
+    pbr::{Material, MeshMaterial3d},
+    prelude::{IntoSystemConfigs, Local, ResMut},
+    reflect::{PartialReflect, Reflect, ReflectMut, ReflectRef},
+};
+
+use crate::{
+    handle::AppliedAssets,
+    impls::{prune_material_bases, MaterialBases},
+    OpacityQuery, OpacityQueryPlugin, OpacitySet,
+};
+
+/// [`QueryData`] for a [`Material`] without an [`OpacityAsset`](crate::OpacityAsset)
+/// implementation, sets the alpha of its first color field through reflection.
+///
+/// Opacity is multiplied into the alpha of the field when first encountered.
+/// If the material is modified elsewhere its new alpha becomes the designed alpha.
+#[derive(Debug, QueryData)]
+pub struct ReflectMaterialQuery<M: Material + Reflect> {
+    pub material: &'static MeshMaterial3d<M>,
+}
+
+/// Designed alpha of a material registered with reflection.
+#[derive(Debug, Clone, Copy)]
+pub struct ReflectMaterialBase {
+    alpha: f32,
+    /// Last alpha written by this crate, if the material differs it was modified elsewhere.
+    written: f32,
+}
+
+impl<M: Material + Reflect> OpacityQuery for ReflectMaterialQuery<M> {
+    type Cx = (
+        ResMut<'static, Assets<M>>,
+        AppliedAssets<'static, M>,
+        ResMut<'static, MaterialBases<M, ReflectMaterialBase>>,
+        // If the missing color field was reported, this system only handles `M`.
+        Local<'static, bool>,
+    );
+
+    fn apply_opacity(
+        this: &mut Self::Item<'_>,
+        (assets, applied, bases, warned): &mut SystemParamItem<Self::Cx>,
+        opacity: f32,
+    ) {
+        let id = this.material.id();
+        if !applied.insert(id, opacity) {
+            return;
+        }
+        let Some(material) = assets.get(id) else {
+            return;
+        };
+        let Some(current) = first_color_alpha(material.as_partial_reflect()) else {
+            if !**warned {
+                **warned = true;
+                warn!(
+                    "{} has no Color, LinearRgba or Srgba field, opacity is not applied.",
+                    M::type_path()
+                );
+            }
+            return;
+        };
+        let base = bases.0.entry(id).or_insert(ReflectMaterialBase {
+            alpha: current,
+            written: current,
+        });
+        if current != base.written {
+            base.alpha = current;
+            base.written = current;
+        }
+        let alpha = base.alpha * opacity;
+        if current == alpha {
+            return;
+        }
+        let Some(material) = assets.get_mut(id) else {
+            return;
+        };
+        set_first_color_alpha(material.as_partial_reflect_mut(), alpha);
+        base.written = alpha;
+    }
+}
+
+/// Added by [`OpacityExtension::register_opacity_material3d_reflect`](crate::OpacityExtension::register_opacity_material3d_reflect),
+/// registering again does nothing.
+#[derive(Debug)]
+pub(crate) struct ReflectMaterialPlugin<M: Material + Reflect>(pub PhantomData<M>);
+
+impl<M: Material + Reflect> Plugin for ReflectMaterialPlugin<M> {
+    fn build(&self, app: &mut App) {
+        if app.is_plugin_added::<Self>() {
+            return;
+        }
+        app.init_resource::<MaterialBases<M, ReflectMaterialBase>>();
+        app.add_event::<AssetEvent<M>>();
+        app.add_systems(
+            PostUpdate,
+            prune_material_bases::<M, ReflectMaterialBase>.in_set(OpacitySet::Calculate),
+        );
+        app.add_plugins(OpacityQueryPlugin::<ReflectMaterialQuery<M>>(PhantomData));
+    }
+
+    fn is_unique(&self) -> bool {
+        false
+    }
+}
+
+/// Returns the alpha of the first top level [`Color`], [`LinearRgba`] or [`Srgba`] field of a struct.
+fn first_color_alpha(value: &dyn PartialReflect) -> Option<f32> {
+    let ReflectRef::Struct(value) = value.reflect_ref() else {
+        return None;
+    };
+    value.iter_fields().find_map(|field| {
+        if let Some(color) = field.try_downcast_ref::<Color>() {
+            return Some(color.alpha());
+        }
+        if let Some(color) = field.try_downcast_ref::<LinearRgba>() {
+            return Some(color.alpha());
+        }
+        field.try_downcast_ref::<Srgba>().map(|color| color.alpha())
+    })
+}
+
+/// Set the alpha of the first top level [`Color`], [`LinearRgba`] or [`Srgba`] field of a struct.
+fn set_first_color_alpha(value: &mut dyn PartialReflect, alpha: f32) {
+    let ReflectMut::Struct(value) = value.reflect_mut() else {
+        return;
+    };
+    for i in 0..value.field_len() {
+        let Some(field) = value.field_at_mut(i) else {
+            continue;
+        };
+        if let Some(color) = field.try_downcast_mut::<Color>() {
+            color.set_alpha(alpha);
+            return;
+        }
+        if let Some(color) = field.try_downcast_mut::<LinearRgba>() {
+            color.set_alpha(alpha);
+            return;
+        }
+        if let Some(color) = field.try_downcast_mut::<Srgba>() {
+            color.set_alpha(alpha);
+            return;
+        }
+    }
+}
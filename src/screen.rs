@@ -0,0 +1,115 @@
+use bevy::{
+    color::{Alpha, Color},
+    prelude::{Commands, Component, Entity, Query},
+    ui::{BackgroundColor, GlobalZIndex, Node, PositionType, TargetCamera, Val},
+};
+
+use crate::{FadeIn, FadeOut, Opacity, UiBaseAlpha, UiOpacity};
+
+/// Insert on a `Camera` to fade the screen to or from a solid color.
+///
+/// This spawns a full-screen [`ScreenFadeOverlay`] node driven by [`FadeIn`] and [`FadeOut`],
+/// and is removed once processed.
+#[derive(Debug, Clone, Copy, Component)]
+pub struct ScreenFade {
+    pub(crate) color: Color,
+    pub(crate) time: f32,
+    pub(crate) cover: bool,
+}
+
+impl ScreenFade {
+    /// Cover the screen with a color within the given time.
+    pub fn to_color(color: Color, time: f32) -> Self {
+        ScreenFade {
+            color,
+            time,
+            cover: true,
+        }
+    }
+
+    /// Reveal the screen from a color within the given time.
+    pub fn from_color(color: Color, time: f32) -> Self {
+        ScreenFade {
+            color,
+            time,
+            cover: false,
+        }
+    }
+
+    /// Fade the screen to black within the given time.
+    pub fn to_black(time: f32) -> Self {
+        Self::to_color(Color::BLACK, time)
+    }
+
+    /// Fade the screen from black within the given time.
+    pub fn from_black(time: f32) -> Self {
+        Self::from_color(Color::BLACK, time)
+    }
+}
+
+/// Full-screen overlay node spawned by [`ScreenFade`] on a camera.
+///
+/// The overlay stays after covering the screen and is despawned after revealing it.
+#[derive(Debug, Clone, Copy, Component)]
+pub struct ScreenFadeOverlay {
+    pub camera: Entity,
+}
+
+pub fn screen_fade(
+    mut commands: Commands,
+    cameras: Query<(Entity, &ScreenFade)>,
+    mut overlays: Query<(
+        Entity,
+        &ScreenFadeOverlay,
+        &mut BackgroundColor,
+        Option<&mut UiBaseAlpha>,
+    )>,
+) {
+    for (camera, fade) in &cameras {
+        commands.entity(camera).remove::<ScreenFade>();
+        let overlay = overlays
+            .iter_mut()
+            .find(|(_, overlay, ..)| overlay.camera == camera);
+        let overlay = match overlay {
+            Some((entity, _, mut color, base)) => {
+                color.0 = fade.color;
+                // Opacity is multiplied into the recorded alpha, not the color.
+                if let Some(mut base) = base {
+                    base.background = fade.color.alpha();
+                }
+                entity
+            }
+            None => commands
+                .spawn((
+                    Node {
+                        position_type: PositionType::Absolute,
+                        width: Val::Percent(100.),
+                        height: Val::Percent(100.),
+                        ..Default::default()
+                    },
+                    BackgroundColor(fade.color),
+                    UiOpacity::Background,
+                    GlobalZIndex(i32::MAX),
+                    TargetCamera(camera),
+                    ScreenFadeOverlay { camera },
+                    if fade.cover {
+                        Opacity::INVISIBLE
+                    } else {
+                        Opacity::OPAQUE
+                    },
+                ))
+                .id(),
+        };
+        if fade.cover {
+            commands
+                .entity(overlay)
+                .remove::<FadeOut>()
+                .insert(FadeIn::new(fade.time));
+        } else {
+            commands
+                .entity(overlay)
+                .remove::<FadeIn>()
+                .insert(FadeOut::new(fade.time));
+        }
+    }
+}
@@ -0,0 +1,62 @@
+use bevy::prelude::{Component, DetectChangesMut, Entity, Event, EventWriter, Query, Res};
+
+use crate::OpacityMap;
+
+/// Emits [`ThresholdCrossed`] when the computed opacity of this entity crosses
+/// any of the listed thresholds in either direction.
+#[derive(Debug, Clone, Component, Default)]
+pub struct OpacityThresholds {
+    pub thresholds: Vec<f32>,
+    pub(crate) last: Option<f32>,
+}
+
+impl OpacityThresholds {
+    pub fn new(thresholds: impl IntoIterator<Item = f32>) -> Self {
+        OpacityThresholds {
+            thresholds: thresholds.into_iter().collect(),
+            last: None,
+        }
+    }
+}
+
+/// [`Event`] sent when the computed opacity of an entity with [`OpacityThresholds`]
+/// crosses a threshold.
+#[derive(Debug, Clone, Copy, PartialEq, Event)]
+pub struct ThresholdCrossed {
+    pub entity: Entity,
+    pub threshold: f32,
+    /// If `true`, opacity went from below the threshold to at or above it.
+    pub rising: bool,
+}
+
+pub fn opacity_thresholds(
+    map: Res<OpacityMap>,
+    mut events: EventWriter<ThresholdCrossed>,
+    mut query: Query<(Entity, &mut OpacityThresholds)>,
+) {
+    for (entity, thresholds) in &mut query {
+        let Some(current) = map.get(entity) else {
+            continue;
+        };
+        if let Some(last) = thresholds.last {
+            for &threshold in &thresholds.thresholds {
+                if last < threshold && current >= threshold {
+                    events.send(ThresholdCrossed {
+                        entity,
+                        threshold,
+                        rising: true,
+                    });
+                } else if last >= threshold && current < threshold {
+                    events.send(ThresholdCrossed {
+                        entity,
+                        threshold,
+                        rising: false,
+                    });
+                }
+            }
+        }
+        thresholds
+            .map_unchanged(|x| &mut x.last)
+            .set_if_neq(Some(current));
+    }
+}
@@ -0,0 +1,125 @@
+use std::{
+    future::Future,
+    pin::Pin,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, Mutex,
+    },
+    task::{Context, Poll, Waker},
+};
+
+use bevy::{
+    ecs::{entity::EntityHashMap, system::EntityCommands},
+    prelude::{Entity, EventReader, Query, ResMut, Resource, World},
+};
+
+use crate::{FadeCompleted, FadeIn, FadeOut};
+
+#[derive(Debug, Default)]
+struct TokenState {
+    done: AtomicBool,
+    waker: Mutex<Option<Waker>>,
+}
+
+impl TokenState {
+    fn resolve(&self) {
+        self.done.store(true, Ordering::Release);
+        if let Some(waker) = self.waker.lock().unwrap().take() {
+            waker.wake();
+        }
+    }
+}
+
+/// A [`Future`] that resolves when the [`FadeCompleted`] event of a fade is sent,
+/// or when the entity is despawned before the fade finishes.
+///
+/// If the fade is replaced by another [`FadeIn`] or [`FadeOut`],
+/// the token resolves when the replacement completes.
+///
+/// Returned by [`OpacityEntityCommands`], can be awaited in tasks like those of
+/// `AsyncComputeTaskPool`. Only resolved while [`OpacityPlugin`](crate::OpacityPlugin) is added.
+#[derive(Debug, Clone)]
+pub struct FadeToken(Arc<TokenState>);
+
+impl FadeToken {
+    /// Returns `true` if the fade has completed.
+    pub fn is_done(&self) -> bool {
+        self.0.done.load(Ordering::Acquire)
+    }
+}
+
+impl Future for FadeToken {
+    type Output = ();
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<()> {
+        if self.is_done() {
+            return Poll::Ready(());
+        }
+        *self.0.waker.lock().unwrap() = Some(cx.waker().clone());
+        // Check again in case the fade completed before the waker was stored.
+        if self.is_done() {
+            Poll::Ready(())
+        } else {
+            Poll::Pending
+        }
+    }
+}
+
+/// Tokens waiting for a [`FadeCompleted`] event.
+#[derive(Debug, Resource, Default)]
+pub(crate) struct PendingFades(EntityHashMap<Vec<Arc<TokenState>>>);
+
+/// Extension methods on [`EntityCommands`] that return an awaitable [`FadeToken`].
+pub trait OpacityEntityCommands {
+    /// Insert [`FadeIn`] and return a token that resolves when it completes.
+    fn fade_in(&mut self, time: f32) -> FadeToken;
+    /// Insert [`FadeOut`] and return a token that resolves when it completes.
+    fn fade_out(&mut self, time: f32) -> FadeToken;
+}
+
+impl OpacityEntityCommands for EntityCommands<'_> {
+    fn fade_in(&mut self, time: f32) -> FadeToken {
+        self.remove::<FadeOut>().insert(FadeIn::new(time));
+        pend(self)
+    }
+
+    fn fade_out(&mut self, time: f32) -> FadeToken {
+        self.remove::<FadeIn>().insert(FadeOut::new(time));
+        pend(self)
+    }
+}
+
+fn pend(commands: &mut EntityCommands) -> FadeToken {
+    let state = Arc::new(TokenState::default());
+    let entity = commands.id();
+    let pending = state.clone();
+    commands.commands().queue(move |world: &mut World| {
+        // Inserted here, since this may run before the plugin is added.
+        world
+            .get_resource_or_insert_with(PendingFades::default)
+            .0
+            .entry(entity)
+            .or_default()
+            .push(pending);
+    });
+    FadeToken(state)
+}
+
+pub fn resolve_fades(
+    mut pending: ResMut<PendingFades>,
+    mut events: EventReader<FadeCompleted>,
+    entities: Query<Entity>,
+) {
+    for event in events.read() {
+        if let Some(tokens) = pending.0.remove(&event.entity()) {
+            tokens.iter().for_each(|x| x.resolve());
+        }
+    }
+    pending.0.retain(|entity, tokens| {
+        if entities.contains(*entity) {
+            return true;
+        }
+        tokens.iter().for_each(|x| x.resolve());
+        false
+    });
+}
@@ -0,0 +1,171 @@
+use bevy::{
+    math::curve::EaseFunction,
+    prelude::{Commands, Component, DetectChangesMut, Entity, Query, Res},
+    time::Time,
+};
+
+use crate::{FadeCurve, Opacity};
+
+/// How values between two keyframes of an [`OpacityTrack`] are computed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum TrackInterpolation {
+    /// Keep the value of the previous keyframe until the next one is reached.
+    Step,
+    /// Linearly interpolate between keyframes, shaped by [`OpacityTrack::with_fade_curve`].
+    #[default]
+    Linear,
+}
+
+/// What an [`OpacityTrack`] does after its last keyframe.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum TrackEnd {
+    /// Stay at the value of the last keyframe.
+    ///
+    /// The value is written once, afterwards opacity can be changed elsewhere
+    /// without the track overwriting it until [`OpacityTrack::restart`] is called.
+    #[default]
+    Hold,
+    /// Restart from the first keyframe.
+    Loop,
+    /// Remove the [`OpacityTrack`], leaving opacity at the value of the last keyframe.
+    Remove,
+}
+
+/// Drives [`Opacity`] through a list of `(time, value)` keyframes.
+///
+/// If [`Opacity`] is not present, insert at `1.0`.
+#[derive(Debug, Clone, Component)]
+#[require(Opacity)]
+pub struct OpacityTrack {
+    pub(crate) keyframes: Vec<(f32, f32)>,
+    pub(crate) interpolation: TrackInterpolation,
+    pub(crate) end: TrackEnd,
+    pub(crate) curve: FadeCurve,
+    pub(crate) current: f32,
+    pub(crate) held: bool,
+}
+
+impl OpacityTrack {
+    /// Create a track from `(time, value)` keyframes, sorted by time.
+    pub fn new(keyframes: impl IntoIterator<Item = (f32, f32)>) -> Self {
+        let mut keyframes: Vec<_> = keyframes.into_iter().collect();
+        keyframes.sort_by(|a, b| a.0.total_cmp(&b.0));
+        OpacityTrack {
+            keyframes,
+            interpolation: TrackInterpolation::Linear,
+            end: TrackEnd::Hold,
+            curve: FadeCurve::Linear,
+            current: 0.,
+            held: false,
+        }
+    }
+
+    /// Linearly move opacity from `current` to `target` within `time`,
+    /// then remove the track.
+    ///
+    /// Spawn this alongside data driven entities to start at a value other than the target.
+    ///
+    /// A time of `0.0` or less, or `NaN`, jumps to the target immediately.
+    pub fn from_to(current: f32, target: f32, time: f32) -> Self {
+        if time <= 0. || time.is_nan() {
+            return Self::new([(0., target)]).with_end(TrackEnd::Remove);
+        }
+        Self::new([(0., current), (time, target)]).with_end(TrackEnd::Remove)
+    }
+
+    /// Move opacity from `current` to `target` within `time` along `curve`,
+    /// then remove the track, see [`OpacityTrack::from_to`].
+    pub fn from_to_curved(current: f32, target: f32, time: f32, curve: FadeCurve) -> Self {
+        Self::from_to(current, target, time).with_fade_curve(curve)
+    }
+
+    /// Set the curve between each pair of keyframes with [`TrackInterpolation::Linear`].
+    ///
+    /// Curves are sampled from `0.0` at the previous keyframe to `1.0` at the next,
+    /// so keyframes are always hit exactly.
+    pub fn with_fade_curve(mut self, curve: FadeCurve) -> Self {
+        self.curve = curve;
+        self
+    }
+
+    /// Set one of bevy's easing functions between each pair of keyframes,
+    /// see [`OpacityTrack::with_fade_curve`].
+    pub fn with_ease(self, ease: EaseFunction) -> Self {
+        self.with_fade_curve(FadeCurve::Ease(ease))
+    }
+
+    /// Set how values between keyframes are computed.
+    pub fn with_interpolation(mut self, interpolation: TrackInterpolation) -> Self {
+        self.interpolation = interpolation;
+        self
+    }
+
+    /// Set the behavior after the last keyframe.
+    pub fn with_end(mut self, end: TrackEnd) -> Self {
+        self.end = end;
+        self
+    }
+
+    /// Play the track again from the first keyframe, including a track held at its end.
+    pub fn restart(&mut self) {
+        self.current = 0.;
+        self.held = false;
+    }
+
+    /// Time of the last keyframe.
+    pub fn duration(&self) -> f32 {
+        self.keyframes.last().map(|(t, _)| *t).unwrap_or(0.)
+    }
+
+    /// Sample the track at a given time, ignoring [`TrackEnd`].
+    ///
+    /// Returns `1.0` if the track is empty.
+    pub fn sample(&self, time: f32) -> f32 {
+        let Some(&(first_time, first)) = self.keyframes.first() else {
+            return 1.;
+        };
+        if time <= first_time {
+            return first;
+        }
+        for window in self.keyframes.windows(2) {
+            let (t0, v0) = window[0];
+            let (t1, v1) = window[1];
+            if time < t1 {
+                return match self.interpolation {
+                    TrackInterpolation::Step => v0,
+                    TrackInterpolation::Linear => {
+                        Opacity::lerp_unclamped(v0, v1, self.curve.sample((time - t0) / (t1 - t0)))
+                    }
+                };
+            }
+        }
+        self.keyframes[self.keyframes.len() - 1].1
+    }
+}
+
+pub fn opacity_track<T: Default + Send + Sync + 'static>(
+    mut commands: Commands,
+    time: Res<Time<T>>,
+    mut query: Query<(Entity, &mut OpacityTrack, &mut Opacity)>,
+) {
+    let dt = time.delta_secs();
+    for (entity, mut track, mut opacity) in &mut query {
+        // The last keyframe has been written, stop so other drivers are not overwritten.
+        if track.held {
+            continue;
+        }
+        track.current += dt;
+        let duration = track.duration();
+        if track.current >= duration {
+            match track.end {
+                TrackEnd::Hold => track.held = true,
+                TrackEnd::Loop if duration > 0. => track.current %= duration,
+                TrackEnd::Loop => (),
+                TrackEnd::Remove => {
+                    commands.entity(entity).remove::<OpacityTrack>();
+                }
+            }
+        }
+        opacity.set_if_neq(Opacity(track.sample(track.current)));
+    }
+}
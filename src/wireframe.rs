@@ -0,0 +1,44 @@
+use bevy::{
+    app::{App, Plugin},
+    color::Alpha,
+    pbr::wireframe::WireframeColor,
+    prelude::IntoSystemConfigs,
+};
+
+use crate::{
+    add_opacity_systems, calculate_opacity,
+    impls::{record_base_alpha, BaseAlphaQuery, ColorComponent},
+    OpacityExtension, OpacitySet,
+};
+
+impl ColorComponent for WireframeColor {
+    fn alpha(&self) -> f32 {
+        self.color.alpha()
+    }
+
+    fn set_alpha(&mut self, alpha: f32) {
+        self.color.set_alpha(alpha);
+    }
+}
+
+/// Added by [`OpacityExtension::register_opacity_wireframe`], registering again does nothing.
+#[derive(Debug)]
+pub(crate) struct WireframeOpacityPlugin;
+
+impl Plugin for WireframeOpacityPlugin {
+    fn build(&self, app: &mut App) {
+        if app.is_plugin_added::<Self>() {
+            return;
+        }
+        add_opacity_systems(app, || {
+            record_base_alpha::<WireframeColor>
+                .after(calculate_opacity)
+                .in_set(OpacitySet::Calculate)
+        });
+        app.register_opacity::<BaseAlphaQuery<WireframeColor>>();
+    }
+
+    fn is_unique(&self) -> bool {
+        false
+    }
+}
@@ -0,0 +1,31 @@
+use bevy::animation::animatable::{Animatable, BlendInput};
+use bevy_mod_opacity::Opacity;
+
+#[test]
+fn interpolate() {
+    let a = Opacity(0.0);
+    let b = Opacity(0.5);
+    assert_eq!(Opacity::interpolate(&a, &b, 0.0), a);
+    assert_eq!(Opacity::interpolate(&a, &b, 0.5), Opacity(0.25));
+    assert_eq!(Opacity::interpolate(&a, &b, 1.0), b);
+}
+
+#[test]
+fn blend() {
+    let blended = Opacity::blend(
+        [
+            BlendInput {
+                weight: 1.0,
+                value: Opacity(0.2),
+                additive: false,
+            },
+            BlendInput {
+                weight: 0.5,
+                value: Opacity(0.6),
+                additive: false,
+            },
+        ]
+        .into_iter(),
+    );
+    assert!((blended.0 - 0.4).abs() < 1e-6);
+}
@@ -0,0 +1,94 @@
+use bevy::{
+    asset::{Asset, AssetId, Assets, Handle},
+    color::Srgba,
+    prelude::{BuildChildren, Component},
+    reflect::TypePath,
+};
+use bevy_mod_opacity::{Opacity, OpacityAsset, OpacityExtension};
+
+mod common;
+
+#[derive(Debug, Clone, TypePath, Asset, Opacity)]
+#[opacity(asset)]
+pub struct Gradient {
+    #[opacity]
+    pub start: Srgba,
+    #[opacity]
+    pub end: Srgba,
+}
+
+#[derive(Debug, Component)]
+pub struct GradientHandle(pub Handle<Gradient>);
+
+impl From<&GradientHandle> for AssetId<Gradient> {
+    fn from(value: &GradientHandle) -> Self {
+        value.0.id()
+    }
+}
+
+#[derive(Debug, Clone, TypePath, Asset)]
+pub struct Counter {
+    pub writes: usize,
+}
+
+impl OpacityAsset for Counter {
+    fn apply_opacity(&mut self, _: f32) {
+        self.writes += 1;
+    }
+}
+
+#[derive(Debug, Component)]
+pub struct CounterHandle(pub Handle<Counter>);
+
+impl From<&CounterHandle> for AssetId<Counter> {
+    fn from(value: &CounterHandle) -> Self {
+        value.0.id()
+    }
+}
+
+#[test]
+fn non_material_asset() {
+    let mut app = common::app();
+    app.init_resource::<Assets<Gradient>>()
+        .register_opacity_asset::<Gradient, GradientHandle>();
+    let handle = app
+        .world_mut()
+        .resource_mut::<Assets<Gradient>>()
+        .add(Gradient {
+            start: Srgba::RED,
+            end: Srgba::BLUE,
+        });
+    app.world_mut()
+        .spawn((Opacity(0.5), GradientHandle(handle.clone())));
+    app.update();
+    let gradient = app
+        .world()
+        .resource::<Assets<Gradient>>()
+        .get(&handle)
+        .unwrap();
+    assert_eq!(gradient.start.alpha, 0.5);
+    assert_eq!(gradient.end.alpha, 0.5);
+}
+
+#[test]
+fn shared_asset() {
+    let mut app = common::app();
+    app.init_resource::<Assets<Counter>>()
+        .register_opacity_asset::<Counter, CounterHandle>();
+    let handle = app
+        .world_mut()
+        .resource_mut::<Assets<Counter>>()
+        .add(Counter { writes: 0 });
+    let root = app.world_mut().spawn(Opacity(0.5)).id();
+    for _ in 0..10 {
+        app.world_mut()
+            .spawn(CounterHandle(handle.clone()))
+            .set_parent(root);
+    }
+    app.update();
+    let counter = app.world().resource::<Assets<Counter>>().get(&handle);
+    assert_eq!(counter.unwrap().writes, 1);
+    app.update();
+    let counter = app.world().resource::<Assets<Counter>>().get(&handle);
+    assert_eq!(counter.unwrap().writes, 2);
+}
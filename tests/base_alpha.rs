@@ -0,0 +1,743 @@
+use bevy::{
+    app::{Last, PostUpdate, Update},
+    asset::{Assets, Handle},
+    color::{Alpha, Color, LinearRgba},
+    math::{Rect, Vec2},
+    pbr::{MeshMaterial3d, StandardMaterial},
+    prelude::{
+        AlphaMode, BuildChildren, Button, Changed, ImageNode, Interaction, IntoSystemConfigs,
+        Query, ResMut, Resource, Sprite, SpriteImageMode, Text, TextureAtlas,
+    },
+    render::camera::{Camera, ClearColorConfig},
+    sprite::{Anchor, BorderRect, SliceScaleMode, TextureSlicer},
+    text::{Text2d, TextColor, TextSpan},
+    ui::{
+        widget::NodeImageMode, BackgroundColor, BorderColor, BoxShadow, Node, Outline, UiRect,
+        UiSystem, Val,
+    },
+};
+use bevy_mod_opacity::{
+    BaseAlpha, BaseColor, FadeBoxShadow, FadeClearColor, FadeIn, FadeOut, Opacity,
+    OutlineBaseAlpha, PremultipliedAlpha, UiBaseAlpha, UiOpacity,
+};
+
+mod common;
+
+#[test]
+fn sprite_base_alpha() {
+    let mut app = common::app();
+    let entity = app
+        .world_mut()
+        .spawn((
+            Sprite::from_color(Color::srgba(1., 1., 1., 0.5), Default::default()),
+            FadeIn::new(1.0),
+        ))
+        .id();
+    common::step(&mut app, 0.5);
+    assert_eq!(
+        app.world().get::<BaseAlpha<Sprite>>(entity),
+        Some(&BaseAlpha::new(0.5))
+    );
+    assert_eq!(
+        app.world().get::<Sprite>(entity).unwrap().color.alpha(),
+        0.25
+    );
+    common::step(&mut app, 1.0);
+    assert!(app.world().get::<FadeIn>(entity).is_none());
+    assert_eq!(
+        app.world().get::<Sprite>(entity).unwrap().color.alpha(),
+        0.5
+    );
+}
+
+#[test]
+fn solid_color_overlay() {
+    let mut app = common::app();
+    let overlay = Color::srgba(0.1, 0.2, 0.3, 0.6);
+    let entity = app
+        .world_mut()
+        .spawn((
+            Sprite::from_color(overlay, Vec2::new(100., 50.)),
+            FadeOut::new(1.0),
+        ))
+        .id();
+    common::step(&mut app, 0.5);
+    let sprite = app.world().get::<Sprite>(entity).unwrap();
+    assert_eq!(sprite.color, overlay.with_alpha(0.3));
+    assert_eq!(sprite.custom_size, Some(Vec2::new(100., 50.)));
+}
+
+#[test]
+fn material_base_alpha() {
+    let mut app = common::app();
+    let handle = app
+        .world_mut()
+        .resource_mut::<Assets<StandardMaterial>>()
+        .add(StandardMaterial {
+            base_color: Color::srgba(1., 1., 1., 0.5),
+            alpha_mode: AlphaMode::Blend,
+            ..Default::default()
+        });
+    let entity = app
+        .world_mut()
+        .spawn((Opacity(0.5), MeshMaterial3d(handle.clone())))
+        .id();
+    app.update();
+    let material = app
+        .world()
+        .resource::<Assets<StandardMaterial>>()
+        .get(&handle)
+        .unwrap();
+    assert_eq!(material.base_color.alpha(), 0.25);
+
+    *app.world_mut().get_mut::<Opacity>(entity).unwrap() = Opacity::FULL;
+    app.update();
+    let material = app
+        .world()
+        .resource::<Assets<StandardMaterial>>()
+        .get(&handle)
+        .unwrap();
+    assert_eq!(material.base_color.alpha(), 0.5);
+}
+
+#[test]
+fn ui_base_alpha() {
+    let mut app = common::app();
+    let entity = app
+        .world_mut()
+        .spawn((
+            Node::default(),
+            BorderColor(Color::srgba(1., 1., 1., 0.5)),
+            BackgroundColor(Color::WHITE),
+            UiOpacity::Both,
+            FadeOut::new(1.0),
+        ))
+        .id();
+    common::step(&mut app, 0.5);
+    assert_eq!(
+        app.world().get::<UiBaseAlpha>(entity),
+        Some(&UiBaseAlpha {
+            background: 1.0,
+            border: 0.5
+        })
+    );
+    assert_eq!(
+        app.world().get::<BorderColor>(entity).unwrap().0.alpha(),
+        0.25
+    );
+    assert_eq!(
+        app.world()
+            .get::<BackgroundColor>(entity)
+            .unwrap()
+            .0
+            .alpha(),
+        0.5
+    );
+}
+
+#[test]
+fn uneven_borders() {
+    let mut app = common::app();
+    let entity = app
+        .world_mut()
+        .spawn((
+            Node {
+                border: UiRect::new(Val::Px(1.), Val::Px(2.), Val::Px(4.), Val::ZERO),
+                ..Default::default()
+            },
+            BorderColor(Color::srgba(0.2, 0.4, 0.6, 0.8)),
+            UiOpacity::Border,
+            FadeOut::new(1.0),
+        ))
+        .id();
+    common::step(&mut app, 0.5);
+    assert_eq!(
+        app.world().get::<BorderColor>(entity).unwrap().0,
+        Color::srgba(0.2, 0.4, 0.6, 0.4)
+    );
+    assert_eq!(
+        app.world().get::<Node>(entity).unwrap().border,
+        UiRect::new(Val::Px(1.), Val::Px(2.), Val::Px(4.), Val::ZERO)
+    );
+}
+
+#[test]
+fn hovered_button() {
+    let mut app = common::app();
+    app.add_systems(
+        Update,
+        |mut query: Query<(&Interaction, &mut BackgroundColor), Changed<Interaction>>| {
+            for (interaction, mut color) in &mut query {
+                color.0 = match interaction {
+                    Interaction::Hovered => Color::srgb(0.5, 0.5, 0.5),
+                    _ => Color::WHITE,
+                };
+            }
+        },
+    );
+    let entity = app
+        .world_mut()
+        .spawn((Button, UiOpacity::Background, FadeOut::new(1.0)))
+        .id();
+    common::step(&mut app, 0.25);
+    assert_eq!(
+        app.world().get::<BackgroundColor>(entity).unwrap().0,
+        Color::WHITE.with_alpha(0.75)
+    );
+
+    *app.world_mut().get_mut::<Interaction>(entity).unwrap() = Interaction::Hovered;
+    common::step(&mut app, 0.25);
+    assert_eq!(
+        app.world().get::<BackgroundColor>(entity).unwrap().0,
+        Color::srgba(0.5, 0.5, 0.5, 0.5)
+    );
+}
+
+#[test]
+fn modified_sprite() {
+    let mut app = common::app();
+    let entity = app
+        .world_mut()
+        .spawn((Sprite::from_color(Color::WHITE, Vec2::ONE), Opacity(0.5)))
+        .id();
+    app.update();
+    app.world_mut().get_mut::<Sprite>(entity).unwrap().color = Color::srgba(1., 0., 0., 0.8);
+    app.update();
+    assert_eq!(
+        app.world().get::<BaseAlpha<Sprite>>(entity),
+        Some(&BaseAlpha::new(0.8))
+    );
+    assert_eq!(
+        app.world().get::<Sprite>(entity).unwrap().color,
+        Color::srgba(1., 0., 0., 0.4)
+    );
+    app.update();
+    assert_eq!(
+        app.world().get::<Sprite>(entity).unwrap().color,
+        Color::srgba(1., 0., 0., 0.4)
+    );
+}
+
+#[test]
+fn hovered_button_alpha() {
+    let mut app = common::app();
+    app.add_systems(
+        Update,
+        |mut query: Query<(&Interaction, &mut BackgroundColor), Changed<Interaction>>| {
+            for (interaction, mut color) in &mut query {
+                color.0 = match interaction {
+                    Interaction::Hovered => Color::srgba(0.5, 0.5, 0.5, 0.8),
+                    _ => Color::WHITE,
+                };
+            }
+        },
+    );
+    let entity = app
+        .world_mut()
+        .spawn((Button, UiOpacity::Background, Opacity(0.5)))
+        .id();
+    app.update();
+    *app.world_mut().get_mut::<Interaction>(entity).unwrap() = Interaction::Hovered;
+    app.update();
+    assert_eq!(
+        app.world().get::<UiBaseAlpha>(entity).unwrap().background,
+        0.8
+    );
+    assert_eq!(
+        app.world().get::<BackgroundColor>(entity).unwrap().0,
+        Color::srgba(0.5, 0.5, 0.5, 0.4)
+    );
+}
+
+#[test]
+fn ui_after_layout() {
+    let mut app = common::app();
+    // Stands in for layout systems that rewrite colors.
+    app.add_systems(
+        PostUpdate,
+        (|mut query: Query<&mut BackgroundColor>| {
+            for mut color in &mut query {
+                color.0.set_alpha(1.);
+            }
+        })
+        .in_set(UiSystem::Layout),
+    );
+    let entity = app
+        .world_mut()
+        .spawn((
+            Node::default(),
+            BackgroundColor(Color::WHITE),
+            UiOpacity::Background,
+            Opacity(0.5),
+        ))
+        .id();
+    app.update();
+    assert_eq!(
+        app.world()
+            .get::<BackgroundColor>(entity)
+            .unwrap()
+            .0
+            .alpha(),
+        0.5
+    );
+}
+
+#[test]
+fn text2d_spans() {
+    let mut app = common::app();
+    let root = app
+        .world_mut()
+        .spawn((
+            Text2d::new("12"),
+            TextColor(Color::srgba(1., 0., 0., 0.5)),
+            FadeOut::new(1.0),
+        ))
+        .id();
+    let span = app
+        .world_mut()
+        .spawn((TextSpan::new("!"), TextColor(Color::WHITE)))
+        .set_parent(root)
+        .id();
+    common::step(&mut app, 0.5);
+    assert_eq!(app.world().get::<TextColor>(root).unwrap().0.alpha(), 0.25);
+    assert_eq!(app.world().get::<TextColor>(span).unwrap().0.alpha(), 0.5);
+}
+
+#[test]
+fn styled_text_spans() {
+    let mut app = common::app();
+    let root = app
+        .world_mut()
+        .spawn((Text::new("HP: "), Opacity(0.5)))
+        .id();
+    let spans = [
+        Color::srgba(1., 0., 0., 1.),
+        Color::srgba(0., 1., 0., 0.8),
+        Color::srgba(0., 0., 1., 0.4),
+    ]
+    .map(|color| {
+        app.world_mut()
+            .spawn((TextSpan::new("|"), TextColor(color)))
+            .set_parent(root)
+            .id()
+    });
+    app.update();
+    assert_eq!(app.world().get::<TextColor>(root).unwrap().0.alpha(), 0.5);
+    let alpha = spans.map(|span| app.world().get::<TextColor>(span).unwrap().0.alpha());
+    assert_eq!(alpha, [0.5, 0.4, 0.2]);
+}
+
+#[test]
+fn box_shadow() {
+    let mut app = common::app();
+    let panel = app
+        .world_mut()
+        .spawn((
+            Node::default(),
+            BoxShadow {
+                color: Color::srgba(0., 0., 0., 0.8),
+                ..Default::default()
+            },
+            FadeBoxShadow,
+            FadeOut::new(1.0),
+        ))
+        .id();
+    common::step(&mut app, 0.5);
+    let alpha = app.world().get::<BoxShadow>(panel).unwrap().color.alpha();
+    assert!((alpha - 0.4).abs() < 1e-5);
+    assert_eq!(
+        app.world().get::<BaseAlpha<BoxShadow>>(panel),
+        Some(&BaseAlpha::new(0.8))
+    );
+}
+
+#[test]
+fn box_shadow_opt_in() {
+    let mut app = common::app();
+    let panel = app
+        .world_mut()
+        .spawn((
+            Node::default(),
+            BoxShadow {
+                color: Color::srgba(0., 0., 0., 0.8),
+                ..Default::default()
+            },
+            FadeOut::new(1.0),
+        ))
+        .id();
+    common::step(&mut app, 0.5);
+    assert_eq!(
+        app.world().get::<BoxShadow>(panel).unwrap().color.alpha(),
+        0.8
+    );
+    assert_eq!(app.world().get::<BaseAlpha<BoxShadow>>(panel), None);
+}
+
+#[test]
+fn image_node_with_box_shadow() {
+    let mut app = common::app();
+    let entity = app
+        .world_mut()
+        .spawn((
+            ImageNode::default().with_color(Color::srgba(1., 1., 1., 0.8)),
+            BoxShadow {
+                color: Color::srgba(0., 0., 0., 0.4),
+                ..Default::default()
+            },
+            FadeBoxShadow,
+            FadeOut::new(1.0),
+        ))
+        .id();
+    common::step(&mut app, 0.5);
+    assert_eq!(
+        app.world().get::<BaseAlpha<ImageNode>>(entity),
+        Some(&BaseAlpha::new(0.8))
+    );
+    assert_eq!(
+        app.world().get::<BaseAlpha<BoxShadow>>(entity),
+        Some(&BaseAlpha::new(0.4))
+    );
+    let image = app.world().get::<ImageNode>(entity).unwrap().color.alpha();
+    let shadow = app.world().get::<BoxShadow>(entity).unwrap().color.alpha();
+    assert!((image - 0.4).abs() < 1e-5);
+    assert!((shadow - 0.2).abs() < 1e-5);
+}
+
+#[test]
+fn atlas_sprite() {
+    let mut app = common::app();
+    let entity = app
+        .world_mut()
+        .spawn((
+            Sprite {
+                texture_atlas: Some(TextureAtlas {
+                    layout: Handle::default(),
+                    index: 3,
+                }),
+                ..Default::default()
+            },
+            FadeOut::new(1.0),
+        ))
+        .id();
+    common::step(&mut app, 0.25);
+    let sprite = app.world().get::<Sprite>(entity).unwrap();
+    assert_eq!(sprite.color.alpha(), 0.75);
+    assert_eq!(sprite.texture_atlas.as_ref().map(|x| x.index), Some(3));
+}
+
+#[test]
+fn atlas_image_node() {
+    let mut app = common::app();
+    let entity = app
+        .world_mut()
+        .spawn((
+            ImageNode {
+                texture_atlas: Some(TextureAtlas {
+                    layout: Handle::default(),
+                    index: 3,
+                }),
+                ..Default::default()
+            },
+            FadeOut::new(1.0),
+        ))
+        .id();
+    common::step(&mut app, 0.25);
+    let image = app.world().get::<ImageNode>(entity).unwrap();
+    assert_eq!(image.color.alpha(), 0.75);
+    assert_eq!(image.texture_atlas.as_ref().map(|x| x.index), Some(3));
+}
+
+#[test]
+fn premultiplied_image_node() {
+    let mut app = common::app();
+    let straight = app
+        .world_mut()
+        .spawn((
+            ImageNode::default().with_color(Color::linear_rgba(1., 0.5, 0., 0.8)),
+            FadeOut::new(1.0),
+        ))
+        .id();
+    let premultiplied = app
+        .world_mut()
+        .spawn((
+            ImageNode::default().with_color(Color::linear_rgba(1., 0.5, 0., 0.8)),
+            PremultipliedAlpha,
+            FadeOut::new(1.0),
+        ))
+        .id();
+    common::step(&mut app, 0.5);
+    assert_eq!(
+        app.world().get::<ImageNode>(straight).unwrap().color,
+        Color::linear_rgba(1., 0.5, 0., 0.4)
+    );
+    assert_eq!(
+        app.world().get::<BaseColor>(premultiplied),
+        Some(&BaseColor(LinearRgba::new(1., 0.5, 0., 0.8)))
+    );
+    assert_eq!(
+        app.world()
+            .get::<ImageNode>(premultiplied)
+            .unwrap()
+            .color
+            .to_linear(),
+        LinearRgba::new(0.5, 0.25, 0., 0.4)
+    );
+}
+
+#[test]
+fn outlined_text() {
+    let mut app = common::app();
+    let entity = app
+        .world_mut()
+        .spawn((
+            Text::new("Outlined"),
+            TextColor(Color::WHITE),
+            Outline::new(Val::Px(2.), Val::ZERO, Color::srgba(0., 0., 0., 0.5)),
+            UiOpacity::Outline,
+            FadeOut::new(1.0),
+        ))
+        .id();
+    common::step(&mut app, 0.5);
+    assert_eq!(
+        app.world().get::<OutlineBaseAlpha>(entity),
+        Some(&OutlineBaseAlpha(0.5))
+    );
+    assert_eq!(app.world().get::<TextColor>(entity).unwrap().0.alpha(), 0.5);
+    assert_eq!(
+        app.world().get::<Outline>(entity).unwrap().color.alpha(),
+        0.25
+    );
+}
+
+#[test]
+fn sliced_sprite() {
+    let slicer = TextureSlicer {
+        border: BorderRect::square(8.),
+        center_scale_mode: SliceScaleMode::Stretch,
+        sides_scale_mode: SliceScaleMode::Tile { stretch_value: 1. },
+        max_corner_scale: 1.,
+    };
+    let mut app = common::app();
+    let sprite = app
+        .world_mut()
+        .spawn((
+            Sprite {
+                color: Color::srgba(1., 1., 1., 0.8),
+                image_mode: SpriteImageMode::Sliced(slicer.clone()),
+                ..Default::default()
+            },
+            FadeOut::new(1.0),
+        ))
+        .id();
+    let node = app
+        .world_mut()
+        .spawn((
+            ImageNode::default().with_mode(NodeImageMode::Sliced(slicer)),
+            FadeOut::new(1.0),
+        ))
+        .id();
+    common::step(&mut app, 0.5);
+    // Every slice is rendered with the single color, so they fade uniformly.
+    let sprite = app.world().get::<Sprite>(sprite).unwrap();
+    assert_eq!(sprite.color.alpha(), 0.4);
+    assert!(matches!(sprite.image_mode, SpriteImageMode::Sliced(_)));
+    let node = app.world().get::<ImageNode>(node).unwrap();
+    assert_eq!(node.color.alpha(), 0.5);
+    assert!(matches!(node.image_mode, NodeImageMode::Sliced(_)));
+}
+
+#[derive(Debug, Default, Resource)]
+pub struct SpriteChanges(usize);
+
+#[test]
+fn unchanged_sprite() {
+    let mut app = common::app();
+    app.init_resource::<SpriteChanges>().add_systems(
+        Last,
+        |query: Query<(), Changed<Sprite>>, mut changes: ResMut<SpriteChanges>| {
+            changes.0 += query.iter().count();
+        },
+    );
+    app.world_mut().spawn((Sprite::default(), Opacity(0.5)));
+    app.update();
+    app.update();
+    let changes = app.world().resource::<SpriteChanges>().0;
+    app.update();
+    app.update();
+    assert_eq!(app.world().resource::<SpriteChanges>().0, changes);
+}
+
+#[test]
+fn sprite_fields_untouched() {
+    let mut app = common::app();
+    let sprite = Sprite {
+        color: Color::srgba(0.2, 0.4, 0.6, 0.8),
+        flip_x: true,
+        flip_y: true,
+        custom_size: Some(Vec2::new(32., 16.)),
+        rect: Some(Rect::new(0., 0., 8., 8.)),
+        anchor: Anchor::BottomLeft,
+        ..Default::default()
+    };
+    let entity = app
+        .world_mut()
+        .spawn((sprite.clone(), FadeOut::new(1.0)))
+        .id();
+    common::step(&mut app, 0.5);
+    let faded = app.world().get::<Sprite>(entity).unwrap();
+    assert_eq!(faded.color, sprite.color.with_alpha(0.4));
+    assert_eq!(faded.flip_x, sprite.flip_x);
+    assert_eq!(faded.flip_y, sprite.flip_y);
+    assert_eq!(faded.custom_size, sprite.custom_size);
+    assert_eq!(faded.rect, sprite.rect);
+    assert_eq!(faded.anchor, sprite.anchor);
+    assert_eq!(faded.image, sprite.image);
+    assert_eq!(faded.image_mode, sprite.image_mode);
+}
+
+#[test]
+fn rich_text_with_icon() {
+    let mut app = common::app();
+    let tooltip = app
+        .world_mut()
+        .spawn((
+            Node::default(),
+            BackgroundColor(Color::BLACK),
+            UiOpacity::Background,
+            FadeOut::new(1.0),
+        ))
+        .id();
+    let icon = app
+        .world_mut()
+        .spawn(ImageNode::default())
+        .set_parent(tooltip)
+        .id();
+    let text = app
+        .world_mut()
+        .spawn(Text::new("Potion"))
+        .set_parent(tooltip)
+        .id();
+    let span = app
+        .world_mut()
+        .spawn(TextSpan::new(" x3"))
+        .set_parent(text)
+        .id();
+    common::step(&mut app, 0.5);
+    let background = app.world().get::<BackgroundColor>(tooltip).unwrap();
+    assert_eq!(background.0.alpha(), 0.5);
+    assert_eq!(
+        app.world().get::<ImageNode>(icon).unwrap().color.alpha(),
+        0.5
+    );
+    assert_eq!(app.world().get::<TextColor>(text).unwrap().0.alpha(), 0.5);
+    assert_eq!(app.world().get::<TextColor>(span).unwrap().0.alpha(), 0.5);
+}
+
+#[test]
+fn clear_color() {
+    let mut app = common::app();
+    let camera = |color| Camera {
+        clear_color: ClearColorConfig::Custom(color),
+        ..Default::default()
+    };
+    let faded = app
+        .world_mut()
+        .spawn((
+            camera(Color::srgba(0., 0., 0., 0.5)),
+            FadeClearColor,
+            FadeOut::new(1.0),
+        ))
+        .id();
+    let ignored = app
+        .world_mut()
+        .spawn((camera(Color::BLACK), FadeOut::new(1.0)))
+        .id();
+    common::step(&mut app, 0.5);
+    let alpha = |entity| match app.world().get::<Camera>(entity).unwrap().clear_color {
+        ClearColorConfig::Custom(color) => color.alpha(),
+        _ => unreachable!(),
+    };
+    assert_eq!(alpha(faded), 0.25);
+    assert_eq!(alpha(ignored), 1.0);
+}
+
+#[test]
+fn clear_color_with_sprite() {
+    let mut app = common::app();
+    let entity = app
+        .world_mut()
+        .spawn((
+            Camera {
+                clear_color: ClearColorConfig::Custom(Color::srgba(0., 0., 0., 0.5)),
+                ..Default::default()
+            },
+            FadeClearColor,
+            Sprite::from_color(Color::srgba(1., 1., 1., 0.8), Vec2::ONE),
+            FadeOut::new(1.0),
+        ))
+        .id();
+    common::step(&mut app, 0.5);
+    assert_eq!(
+        app.world().get::<BaseAlpha<Camera>>(entity),
+        Some(&BaseAlpha::new(0.5))
+    );
+    assert_eq!(
+        app.world().get::<BaseAlpha<Sprite>>(entity),
+        Some(&BaseAlpha::new(0.8))
+    );
+    let ClearColorConfig::Custom(color) = app.world().get::<Camera>(entity).unwrap().clear_color
+    else {
+        unreachable!()
+    };
+    assert_eq!(color.alpha(), 0.25);
+    assert_eq!(
+        app.world().get::<Sprite>(entity).unwrap().color.alpha(),
+        0.4
+    );
+}
+
+#[test]
+fn outlined_button() {
+    let mut app = common::app();
+    let button = app
+        .world_mut()
+        .spawn((
+            Node::default(),
+            BackgroundColor(Color::WHITE),
+            Outline::new(Val::Px(2.), Val::ZERO, Color::BLACK),
+            UiOpacity::All,
+            FadeOut::new(1.0),
+        ))
+        .id();
+    common::step(&mut app, 0.5);
+    let world = app.world();
+    assert_eq!(world.get::<BackgroundColor>(button).unwrap().0.alpha(), 0.5);
+    assert_eq!(world.get::<Outline>(button).unwrap().color.alpha(), 0.5);
+    common::step(&mut app, 0.25);
+    let world = app.world();
+    assert_eq!(
+        world.get::<BackgroundColor>(button).unwrap().0.alpha(),
+        0.25
+    );
+    assert_eq!(world.get::<Outline>(button).unwrap().color.alpha(), 0.25);
+}
+
+#[test]
+fn outline_opt_in() {
+    let mut app = common::app();
+    let entity = app
+        .world_mut()
+        .spawn((
+            Node::default(),
+            BackgroundColor(Color::WHITE),
+            Outline::new(Val::Px(2.), Val::ZERO, Color::BLACK),
+            UiOpacity::Background,
+            FadeOut::new(1.0),
+        ))
+        .id();
+    common::step(&mut app, 0.5);
+    let world = app.world();
+    assert_eq!(world.get::<BackgroundColor>(entity).unwrap().0.alpha(), 0.5);
+    assert_eq!(world.get::<Outline>(entity).unwrap().color.alpha(), 1.0);
+    assert!(world.get::<OutlineBaseAlpha>(entity).is_none());
+}
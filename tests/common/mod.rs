@@ -0,0 +1,30 @@
+#![allow(dead_code)]
+
+use std::time::Duration;
+
+use bevy::{
+    app::App,
+    asset::Assets,
+    pbr::StandardMaterial,
+    sprite::ColorMaterial,
+    time::{Time, Virtual},
+};
+use bevy_mod_opacity::OpacityPlugin;
+
+/// An [`App`] with [`OpacityPlugin`] that does not require rendering.
+pub fn app() -> App {
+    let mut app = App::new();
+    app.init_resource::<Time<Virtual>>()
+        .init_resource::<Assets<ColorMaterial>>()
+        .init_resource::<Assets<StandardMaterial>>()
+        .add_plugins(OpacityPlugin);
+    app
+}
+
+/// Advance virtual time by `secs` and run one update.
+pub fn step(app: &mut App, secs: f32) {
+    app.world_mut()
+        .resource_mut::<Time<Virtual>>()
+        .advance_by(Duration::from_secs_f32(secs));
+    app.update();
+}
@@ -0,0 +1,22 @@
+use bevy::prelude::{BuildChildren, Sprite};
+use bevy_mod_opacity::{Opacity, OpacityDebugPlugin, OpacityMap};
+
+mod common;
+
+#[test]
+fn debug_plugin() {
+    let mut app = common::app();
+    app.add_plugins(OpacityDebugPlugin);
+    let root = app.world_mut().spawn(Opacity(0.5)).id();
+    let child = app
+        .world_mut()
+        .spawn(Sprite::default())
+        .set_parent(root)
+        .id();
+    common::step(&mut app, 0.1);
+    app.world_mut().get_mut::<Opacity>(root).unwrap().0 = 0.25;
+    common::step(&mut app, 0.1);
+    app.world_mut().despawn(child);
+    common::step(&mut app, 0.1);
+    assert_eq!(app.world().resource::<OpacityMap>().get(root), Some(0.25));
+}
@@ -1,13 +1,17 @@
+mod common;
+
 use bevy::{
-    app::App,
-    asset::Asset,
-    color::Srgba,
-    pbr::{ExtendedMaterial, Material, MaterialExtension, StandardMaterial},
-    prelude::Component,
+    app::{App, Last},
+    asset::{Asset, Assets},
+    color::{Alpha, Color, Srgba},
+    pbr::{ExtendedMaterial, Material, MaterialExtension, MeshMaterial3d, StandardMaterial},
+    prelude::{Changed, Component, Query, ResMut, Resource},
     reflect::TypePath,
     render::render_resource::AsBindGroup,
 };
-use bevy_mod_opacity::{Opacity, OpacityExtension, OpacityPlugin};
+use bevy_mod_opacity::{
+    impl_opacity_material, Opacity, OpacityAsset, OpacityExtension, OpacityPlugin,
+};
 
 #[derive(Debug, Component, Opacity)]
 pub struct MyColor {
@@ -18,6 +22,32 @@ pub struct MyColor {
     pub a: f32,
 }
 
+/// A downstream color type, only implementing bevy's [`Alpha`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Gray(f32, f32);
+
+impl Alpha for Gray {
+    fn with_alpha(&self, alpha: f32) -> Self {
+        Gray(self.0, alpha)
+    }
+
+    fn alpha(&self) -> f32 {
+        self.1
+    }
+
+    fn set_alpha(&mut self, alpha: f32) {
+        self.1 = alpha;
+    }
+}
+
+#[derive(Debug, Component, Opacity)]
+pub struct MyGray {
+    #[opacity]
+    pub color: Gray,
+    #[opacity]
+    pub shadow: Option<Gray>,
+}
+
 #[derive(Debug, Clone, TypePath, Asset, Opacity, AsBindGroup)]
 #[opacity(asset)]
 pub struct MyColorMaterial {
@@ -27,6 +57,19 @@ pub struct MyColorMaterial {
 
 impl Material for MyColorMaterial {}
 
+#[derive(Debug, Clone, TypePath, Asset, Opacity, AsBindGroup)]
+#[opacity(asset)]
+pub struct MyOptionalColorMaterial {
+    #[opacity]
+    pub color: Srgba,
+    #[opacity]
+    pub tint: Option<Srgba>,
+}
+
+impl Material for MyOptionalColorMaterial {}
+
+impl_opacity_material!(MyOptionalColorMaterialPlugin, MyOptionalColorMaterial, 3d);
+
 #[derive(Debug, Clone, AsBindGroup, TypePath, Asset, Opacity)]
 #[opacity(extends = StandardMaterial)]
 pub struct MyColorMaterialExt {
@@ -45,13 +88,210 @@ pub struct MyColorMaterialExtMask {
 
 impl MaterialExtension for MyColorMaterialExtMask {}
 
+#[derive(Debug, Clone, TypePath, Opacity)]
+#[opacity(nested)]
+pub struct Layer {
+    #[opacity]
+    pub tint: Srgba,
+}
+
+#[derive(Debug, Clone, TypePath, Asset, Opacity, AsBindGroup)]
+#[opacity(asset)]
+pub struct MyLayeredMaterial {
+    #[opacity]
+    pub color: Srgba,
+    #[opacity(nested)]
+    pub layer: Layer,
+}
+
+impl Material for MyLayeredMaterial {}
+
+#[derive(Debug, Component, Opacity)]
+pub struct Tint<T: Send + Sync + 'static> {
+    #[opacity]
+    pub a: T,
+    #[opacity]
+    pub b: Option<T>,
+}
+
+#[derive(Debug, Clone, TypePath, Asset, Opacity, AsBindGroup)]
+#[opacity(asset)]
+pub struct MyGenericMaterial<T: Layered> {
+    #[opacity]
+    pub color: Srgba,
+    #[opacity(nested)]
+    pub layer: T,
+}
+
+pub trait Layered:
+    bevy_mod_opacity::OpacityNested + Clone + TypePath + Send + Sync + 'static
+{
+}
+
+impl Layered for Layer {}
+
+impl<T: Layered> Material for MyGenericMaterial<T> {}
+
 #[test]
 fn test() {
     let _app = App::new()
         .add_plugins(OpacityPlugin)
         .register_opacity_component::<MyColor>()
         .register_opacity_material3d::<MyColorMaterial>()
+        .register_opacity_material3d::<MyLayeredMaterial>()
+        .register_opacity_component::<Tint<Srgba>>()
+        .register_opacity_component::<Tint<f32>>()
+        .register_opacity_material3d::<MyGenericMaterial<Layer>>()
+        .add_plugins(MyOptionalColorMaterialPlugin)
         .register_opacity_material3d::<ExtendedMaterial<StandardMaterial, MyColorMaterialExt>>()
         .register_opacity_material3d::<ExtendedMaterial<StandardMaterial, MyColorMaterialExtMask>>(
         );
 }
+
+#[test]
+fn optional_field() {
+    let mut material = MyOptionalColorMaterial {
+        color: Srgba::WHITE,
+        tint: Some(Srgba::RED),
+    };
+    material.apply_opacity(0.5);
+    assert_eq!(material.color.alpha, 0.5);
+    assert_eq!(material.tint.map(|x| x.alpha), Some(0.5));
+
+    material.tint = None;
+    material.apply_opacity(0.25);
+    assert_eq!(material.color.alpha, 0.25);
+    assert_eq!(material.tint, None);
+}
+
+#[test]
+fn alpha_field() {
+    let mut app = common::app();
+    app.register_opacity_component::<MyGray>();
+    let entity = app
+        .world_mut()
+        .spawn((
+            Opacity(0.5),
+            MyGray {
+                color: Gray(0.2, 1.),
+                shadow: Some(Gray(0., 1.)),
+            },
+        ))
+        .id();
+    app.update();
+    let gray = app.world().get::<MyGray>(entity).unwrap();
+    assert_eq!(gray.color, Gray(0.2, 0.5));
+    assert_eq!(gray.shadow, Some(Gray(0., 0.5)));
+}
+
+#[test]
+fn nested_field() {
+    let mut material = MyLayeredMaterial {
+        color: Srgba::WHITE,
+        layer: Layer { tint: Srgba::RED },
+    };
+    material.apply_opacity(0.5);
+    assert_eq!(material.color.alpha, 0.5);
+    assert_eq!(material.layer.tint.alpha, 0.5);
+}
+
+#[test]
+fn generic_field() {
+    let mut app = common::app();
+    app.register_opacity_component::<Tint<Srgba>>();
+    let entity = app
+        .world_mut()
+        .spawn((
+            Opacity(0.5),
+            Tint {
+                a: Srgba::WHITE,
+                b: Some(Srgba::RED),
+            },
+        ))
+        .id();
+    app.update();
+    let tint = app.world().get::<Tint<Srgba>>(entity).unwrap();
+    assert_eq!(tint.a.alpha, 0.5);
+    assert_eq!(tint.b.map(|x| x.alpha), Some(0.5));
+
+    let mut material = MyGenericMaterial {
+        color: Srgba::WHITE,
+        layer: Layer { tint: Srgba::RED },
+    };
+    material.apply_opacity(0.25);
+    assert_eq!(material.color.alpha, 0.25);
+    assert_eq!(material.layer.tint.alpha, 0.25);
+}
+
+#[derive(Debug, Default, Resource)]
+pub struct MyColorChanges(usize);
+
+#[test]
+fn unchanged_component() {
+    let mut app = common::app();
+    app.register_opacity_component::<MyColor>()
+        .init_resource::<MyColorChanges>()
+        .add_systems(
+            Last,
+            |query: Query<(), Changed<MyColor>>, mut changes: ResMut<MyColorChanges>| {
+                changes.0 += query.iter().count();
+            },
+        );
+    app.world_mut().spawn((
+        Opacity(0.5),
+        MyColor {
+            r: 1.,
+            g: 1.,
+            b: 1.,
+            a: 1.,
+        },
+    ));
+    app.update();
+    app.update();
+    let changes = app.world().resource::<MyColorChanges>().0;
+    app.update();
+    app.update();
+    assert_eq!(app.world().resource::<MyColorChanges>().0, changes);
+}
+
+#[test]
+fn extended_material_base_alpha() {
+    type Extended = ExtendedMaterial<StandardMaterial, MyColorMaterialExt>;
+    let mut app = common::app();
+    app.init_resource::<Assets<Extended>>()
+        .register_opacity_material3d::<Extended>();
+    let handle = app
+        .world_mut()
+        .resource_mut::<Assets<Extended>>()
+        .add(Extended {
+            base: StandardMaterial {
+                base_color: Color::srgba(1., 1., 1., 0.8),
+                ..Default::default()
+            },
+            extension: MyColorMaterialExt {
+                color: Srgba::WHITE,
+            },
+        });
+    let entity = app
+        .world_mut()
+        .spawn((Opacity(0.5), MeshMaterial3d(handle.clone())))
+        .id();
+    app.update();
+    let material = app
+        .world()
+        .resource::<Assets<Extended>>()
+        .get(&handle)
+        .unwrap();
+    assert_eq!(material.base.base_color.alpha(), 0.4);
+    assert_eq!(material.extension.color.alpha, 0.5);
+
+    *app.world_mut().get_mut::<Opacity>(entity).unwrap() = Opacity::OPAQUE;
+    app.update();
+    let material = app
+        .world()
+        .resource::<Assets<Extended>>()
+        .get(&handle)
+        .unwrap();
+    assert_eq!(material.base.base_color.alpha(), 0.8);
+    assert_eq!(material.extension.color.alpha, 1.0);
+}
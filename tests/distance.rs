@@ -0,0 +1,50 @@
+use bevy::{prelude::Camera, transform::components::GlobalTransform};
+use bevy_mod_opacity::{DistanceFade, Opacity};
+
+mod common;
+
+#[test]
+fn distance_to_camera() {
+    let mut app = common::app();
+    let camera = app
+        .world_mut()
+        .spawn((Camera::default(), GlobalTransform::IDENTITY))
+        .id();
+    let entity = app
+        .world_mut()
+        .spawn((
+            DistanceFade::new(10.0, 20.0),
+            GlobalTransform::from_xyz(0., 0., 15.),
+        ))
+        .id();
+    app.update();
+    assert_eq!(app.world().get::<Opacity>(entity), Some(&Opacity(0.5)));
+
+    *app.world_mut().get_mut::<GlobalTransform>(camera).unwrap() =
+        GlobalTransform::from_xyz(0., 0., 30.);
+    app.update();
+    assert_eq!(app.world().get::<Opacity>(entity), Some(&Opacity(0.5)));
+
+    *app.world_mut().get_mut::<GlobalTransform>(camera).unwrap() =
+        GlobalTransform::from_xyz(0., 0., 40.);
+    app.update();
+    assert_eq!(
+        app.world().get::<Opacity>(entity),
+        Some(&Opacity::INVISIBLE)
+    );
+}
+
+#[test]
+fn missing_camera() {
+    let mut app = common::app();
+    let entity = app
+        .world_mut()
+        .spawn((
+            DistanceFade::new(10.0, 20.0),
+            GlobalTransform::from_xyz(0., 0., 15.),
+            Opacity(0.25),
+        ))
+        .id();
+    app.update();
+    assert_eq!(app.world().get::<Opacity>(entity), Some(&Opacity(0.25)));
+}
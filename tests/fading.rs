@@ -0,0 +1,774 @@
+use bevy::{
+    app::App,
+    color::Alpha,
+    ecs::event::Events,
+    math::curve::EaseFunction,
+    prelude::{BuildChildren, Children, Component, Parent, Resource, Sprite, Transform, World},
+};
+use bevy_mod_opacity::{
+    reset_fade, Blink, DespawnMode, FadeCompleted, FadeConfig, FadeCurve, FadeIn, FadeOut, Opacity,
+    OpacityCommands, OpacityFloor, OpacityTrack, Toast, ToastPhase,
+};
+
+mod common;
+
+#[test]
+fn fade_in_step() {
+    let mut opacity = Opacity::INVISIBLE;
+    let mut fade = FadeIn::new(1.0);
+    assert!(!fade.step(&mut opacity, 0.25));
+    assert_eq!(opacity, Opacity(0.25));
+    assert!(!fade.step(&mut opacity, 0.5));
+    assert_eq!(opacity, Opacity(0.75));
+    assert!(fade.step(&mut opacity, 0.5));
+    assert_eq!(opacity, Opacity::FULL);
+}
+
+#[test]
+fn fade_out_step() {
+    let mut opacity = Opacity::FULL;
+    let mut fade = FadeOut::new(2.0);
+    assert!(!fade.step(&mut opacity, 0.5));
+    assert_eq!(opacity, Opacity(0.75));
+    assert!(!fade.step(&mut opacity, 1.0));
+    assert_eq!(opacity, Opacity(0.25));
+}
+
+#[test]
+fn fade_out_step_despawn() {
+    let mut opacity = Opacity::FULL;
+    let mut fade = FadeOut::new(1.0).with_curve(|x| x * x);
+    assert!(!fade.step(&mut opacity, 0.5));
+    assert_eq!(opacity, Opacity(0.75));
+    assert!(fade.step(&mut opacity, 0.5));
+    assert_eq!(opacity, Opacity::INVISIBLE);
+}
+
+#[test]
+fn blink_toggles() {
+    let mut opacity = Opacity::FULL;
+    let mut blink = Blink::new(3, 0.5);
+    let mut toggles = 0;
+    let mut last = opacity;
+    let mut finished = false;
+    for _ in 0..20 {
+        finished = blink.step(&mut opacity, 0.125);
+        if opacity != last {
+            toggles += 1;
+            last = opacity;
+        }
+        if finished {
+            break;
+        }
+    }
+    assert!(finished);
+    // low then high for each of the 3 blinks.
+    assert_eq!(toggles, 6);
+    assert_eq!(opacity, Opacity::FULL);
+    assert_eq!(blink.remaining(), 0);
+}
+
+#[test]
+fn blink_hierarchy() {
+    let mut app = common::app();
+    let root = app
+        .world_mut()
+        .spawn(Blink::new(2, 1.0).with_low(0.25))
+        .id();
+    let child = app
+        .world_mut()
+        .spawn(Sprite::default())
+        .set_parent(root)
+        .id();
+    common::step(&mut app, 0.25);
+    assert_eq!(
+        app.world().get::<Sprite>(child).unwrap().color.alpha(),
+        0.25
+    );
+    common::step(&mut app, 0.5);
+    assert_eq!(app.world().get::<Sprite>(child).unwrap().color.alpha(), 1.0);
+    common::step(&mut app, 0.5);
+    assert_eq!(
+        app.world().get::<Sprite>(child).unwrap().color.alpha(),
+        0.25
+    );
+    common::step(&mut app, 1.0);
+    assert_eq!(app.world().get::<Sprite>(child).unwrap().color.alpha(), 1.0);
+    assert!(app.world().get::<Blink>(root).is_none());
+}
+
+#[derive(Debug, Component)]
+pub struct Contents;
+
+#[test]
+fn fade_out_despawn_marked() {
+    let mut app = common::app();
+    let root = app
+        .world_mut()
+        .spawn(FadeOut::new(1.0).despawn_marked::<Contents>())
+        .id();
+    let frame = app.world_mut().spawn_empty().set_parent(root).id();
+    let contents = app.world_mut().spawn(Contents).set_parent(root).id();
+    let nested = app.world_mut().spawn(Contents).set_parent(frame).id();
+    let inner = app.world_mut().spawn_empty().set_parent(contents).id();
+    common::step(&mut app, 0.5);
+    assert!(app.world().get_entity(contents).is_ok());
+    common::step(&mut app, 0.6);
+    assert!(app.world().get_entity(root).is_ok());
+    assert!(app.world().get_entity(frame).is_ok());
+    assert!(app.world().get_entity(contents).is_err());
+    assert!(app.world().get_entity(nested).is_err());
+    assert!(app.world().get_entity(inner).is_err());
+    assert!(app.world().get::<FadeOut>(root).is_none());
+}
+
+#[derive(Debug, Default, Resource)]
+pub struct Completions(u32);
+
+#[test]
+fn fade_out_then() {
+    let mut app = common::app();
+    app.init_resource::<Completions>();
+    let fade = FadeOut::new(1.0);
+    let root = app
+        .world_mut()
+        .spawn(
+            fade.then(|world: &mut World| world.resource_mut::<Completions>().0 += 1)
+                .despawn_marked::<Contents>(),
+        )
+        .id();
+    // `FadeOut` stays `Copy`, the command lives in its own component.
+    let other = app.world_mut().spawn(fade).id();
+    common::step(&mut app, 0.5);
+    assert_eq!(app.world().resource::<Completions>().0, 0);
+    common::step(&mut app, 0.6);
+    assert_eq!(app.world().resource::<Completions>().0, 1);
+    assert!(app.world().get_entity(root).is_ok());
+    assert!(app.world().get_entity(other).is_err());
+    app.world_mut().entity_mut(root).insert(FadeOut::new(1.0));
+    common::step(&mut app, 1.5);
+    assert_eq!(app.world().resource::<Completions>().0, 1);
+}
+
+#[test]
+fn zero_time() {
+    let mut opacity = Opacity::INVISIBLE;
+    assert!(FadeIn::new(0.0).step(&mut opacity, 0.0));
+    assert_eq!(opacity, Opacity::FULL);
+    let mut opacity = Opacity::INVISIBLE;
+    assert!(FadeIn::new(0.0).with_curve(|x| x).step(&mut opacity, 0.0));
+    assert_eq!(opacity, Opacity::FULL);
+
+    let mut opacity = Opacity::FULL;
+    assert!(FadeOut::new(0.0).step(&mut opacity, 0.016));
+    assert_eq!(opacity, Opacity::INVISIBLE);
+    let mut opacity = Opacity::FULL;
+    assert!(FadeOut::new(0.0).with_curve(|x| x).step(&mut opacity, 0.0));
+    assert_eq!(opacity, Opacity::INVISIBLE);
+
+    let mut app = common::app();
+    let entity = app.world_mut().spawn(FadeIn::new(0.0)).id();
+    common::step(&mut app, 0.0);
+    assert_eq!(app.world().get::<Opacity>(entity), Some(&Opacity::FULL));
+    assert!(app.world().get::<FadeIn>(entity).is_none());
+}
+
+#[test]
+fn fade_out_target() {
+    let mut opacity = Opacity::FULL;
+    let mut fade = FadeOut::new(1.0).with_target(0.5);
+    assert!(!fade.step(&mut opacity, 0.5));
+    assert_eq!(opacity, Opacity(0.75));
+    assert!(fade.step(&mut opacity, 0.5));
+    assert_eq!(opacity, Opacity(0.5));
+
+    let mut app = common::app();
+    let entity = app
+        .world_mut()
+        .spawn(FadeOut::new(1.0).with_target(0.25))
+        .id();
+    common::step(&mut app, 0.5);
+    assert!(app.world().get_entity(entity).is_ok());
+    common::step(&mut app, 0.5);
+    assert!(app.world().get_entity(entity).is_err());
+}
+
+#[test]
+fn fade_out_below_target() {
+    let mut opacity = Opacity(0.2);
+    let mut fade = FadeOut::new(1.0).with_target(0.5);
+    assert!(fade.step(&mut opacity, 0.25));
+    assert_eq!(opacity, Opacity(0.2));
+
+    let mut fade = FadeOut::new(1.0).with_target(0.5).with_curve(|x| x);
+    assert!(fade.step(&mut opacity, 0.25));
+    assert_eq!(opacity, Opacity(0.2));
+
+    let mut fade = FadeOut::new(0.0).with_target(0.5);
+    assert!(fade.step(&mut opacity, 0.25));
+    assert_eq!(opacity, Opacity(0.2));
+}
+
+#[test]
+fn fade_in_despawning() {
+    let mut app = common::app();
+    let entity = app
+        .world_mut()
+        .spawn((Opacity(0.5), FadeIn::new(1.0).despawning()))
+        .id();
+    let child = app.world_mut().spawn_empty().set_parent(entity).id();
+    common::step(&mut app, 0.25);
+    assert_eq!(app.world().get::<Opacity>(entity), Some(&Opacity(0.75)));
+    common::step(&mut app, 0.5);
+    assert!(app.world().get_entity(entity).is_err());
+    assert!(app.world().get_entity(child).is_err());
+
+    let mut fade = FadeIn::new(1.0).despawning();
+    fade.set_despawns(false);
+    let entity = app.world_mut().spawn(fade).id();
+    common::step(&mut app, 1.5);
+    assert!(app.world().get::<FadeIn>(entity).is_none());
+    assert_eq!(app.world().get::<Opacity>(entity), Some(&Opacity::FULL));
+}
+
+#[test]
+fn fade_config_no_despawn() {
+    let mut app = common::app();
+    app.insert_resource(FadeConfig {
+        despawn_on_fade_out: false,
+        ..Default::default()
+    });
+    let entity = app.world_mut().spawn(FadeOut::new(1.0)).id();
+    let child = app.world_mut().spawn_empty().set_parent(entity).id();
+    common::step(&mut app, 1.5);
+    assert_eq!(
+        app.world().get::<Opacity>(entity),
+        Some(&Opacity::INVISIBLE)
+    );
+    assert!(app.world().get::<FadeOut>(entity).is_none());
+    assert!(app.world().get_entity(child).is_ok());
+
+    app.insert_resource(FadeConfig::default());
+    app.world_mut().entity_mut(entity).insert(FadeOut::new(1.0));
+    common::step(&mut app, 1.5);
+    assert!(app.world().get_entity(entity).is_err());
+}
+
+#[test]
+fn fade_in_from_existing() {
+    let mut app = common::app();
+    let entity = app.world_mut().spawn(Opacity(0.5)).id();
+    app.world_mut().entity_mut(entity).insert(FadeIn::new(1.0));
+    common::step(&mut app, 0.0);
+    assert_eq!(app.world().get::<Opacity>(entity), Some(&Opacity(0.5)));
+    common::step(&mut app, 0.25);
+    assert_eq!(app.world().get::<Opacity>(entity), Some(&Opacity(0.75)));
+
+    let mut opacity = Opacity(0.5);
+    let mut fade = FadeIn::new(1.0).with_curve(|x| x);
+    assert!(!fade.step(&mut opacity, 0.5));
+    assert_eq!(opacity, Opacity(0.75));
+}
+
+#[test]
+fn fade_out_curve_from_existing() {
+    let mut opacity = Opacity(0.5);
+    let mut fade = FadeOut::new(1.0).with_fade_curve(FadeCurve::Linear);
+    assert!(!fade.step(&mut opacity, 0.5));
+    assert_eq!(opacity, Opacity(0.25));
+    assert!(fade.step(&mut opacity, 0.5));
+    assert_eq!(opacity, Opacity::INVISIBLE);
+
+    let mut opacity = Opacity(0.6);
+    let mut fade = FadeOut::new(1.0).with_target(0.2).with_curve(|x| x);
+    assert!(!fade.step(&mut opacity, 0.5));
+    assert!((opacity.0 - 0.4).abs() < 1e-5);
+}
+
+#[test]
+fn fade_curve_presets() {
+    let curve = FadeCurve::EaseInOut;
+    let mut last = curve.sample(0.0);
+    assert_eq!(last, 0.0);
+    for i in 1..=100 {
+        let x = i as f32 / 100.;
+        let y = curve.sample(x);
+        assert!(y >= last);
+        assert!((y + curve.sample(1. - x) - 1.).abs() < 1e-5);
+        last = y;
+    }
+    assert_eq!(last, 1.0);
+    assert_eq!(FadeCurve::EaseOut.sample(2.0), 1.0);
+
+    let mut opacity = Opacity::INVISIBLE;
+    let mut fade = FadeIn::new(1.0).with_fade_curve(FadeCurve::EaseOut);
+    assert!(!fade.step(&mut opacity, 0.5));
+    assert_eq!(opacity, Opacity(0.75));
+    assert!(fade.step(&mut opacity, 0.5));
+    assert_eq!(opacity, Opacity::FULL);
+
+    let mut opacity = Opacity::FULL;
+    let mut fade = FadeOut::new(1.0).with_fade_curve(FadeCurve::EaseIn);
+    assert!(!fade.step(&mut opacity, 0.5));
+    assert_eq!(opacity, Opacity(0.75));
+    assert!(fade.step(&mut opacity, 0.75));
+    assert_eq!(opacity, Opacity::INVISIBLE);
+}
+
+#[test]
+fn ease_functions() {
+    for ease in [EaseFunction::QuadraticInOut, EaseFunction::SineOut] {
+        let curve = FadeCurve::from(ease);
+        let mut last = curve.sample(0.0);
+        assert_eq!(last, 0.0);
+        for i in 1..=100 {
+            let y = curve.sample(i as f32 / 100.);
+            assert!(y >= last);
+            last = y;
+        }
+        assert!((last - 1.0).abs() < 1e-5);
+    }
+
+    // Bounces touch the end value early, the fade still runs its full duration.
+    let mut opacity = Opacity::INVISIBLE;
+    let mut fade = FadeIn::new(1.0).with_ease(EaseFunction::BounceOut);
+    for _ in 0..9 {
+        assert!(!fade.step(&mut opacity, 0.1));
+        assert!((0.0..=1.0).contains(&opacity.0));
+    }
+    assert!(fade.step(&mut opacity, 0.2));
+    assert_eq!(opacity, Opacity::FULL);
+
+    let mut opacity = Opacity::FULL;
+    let mut fade = FadeOut::new(1.0).with_ease(EaseFunction::QuadraticIn);
+    assert!(!fade.step(&mut opacity, 0.5));
+    assert_eq!(opacity, Opacity(0.75));
+    assert!(fade.step(&mut opacity, 0.5));
+    assert_eq!(opacity, Opacity::INVISIBLE);
+}
+
+#[test]
+fn reset_pooled() {
+    let mut opacity = Opacity(0.2);
+    opacity.reset_opaque();
+    assert_eq!(opacity, Opacity::FULL);
+
+    let mut app = common::app();
+    let entity = app.world_mut().spawn(FadeOut::new(1.0)).id();
+    common::step(&mut app, 0.5);
+    reset_fade(&mut app.world_mut().commands(), entity);
+    app.world_mut().flush();
+    common::step(&mut app, 1.0);
+    assert!(app.world().get::<FadeOut>(entity).is_none());
+    assert_eq!(app.world().get::<Opacity>(entity), Some(&Opacity::FULL));
+}
+
+#[test]
+fn fade_out_subtree() {
+    let mut app = common::app();
+    let root = app.world_mut().spawn(Opacity(0.5)).id();
+    let child = app.world_mut().spawn_empty().set_parent(root).id();
+    app.world_mut().commands().fade_out_subtree(root, 1.0);
+    app.world_mut().flush();
+    assert_eq!(app.world().get::<Opacity>(root), Some(&Opacity(0.5)));
+    common::step(&mut app, 0.25);
+    assert_eq!(app.world().get::<Opacity>(root), Some(&Opacity(0.25)));
+    common::step(&mut app, 0.5);
+    assert!(app.world().get_entity(root).is_err());
+    assert!(app.world().get_entity(child).is_err());
+}
+
+#[test]
+fn fade_out_subtree_drivers() {
+    let mut app = common::app();
+    app.init_resource::<Completions>();
+    let root = app.world_mut().spawn(Opacity::FULL).id();
+    let fading = app
+        .world_mut()
+        .spawn(
+            FadeOut::new(0.5).then(|world: &mut World| world.resource_mut::<Completions>().0 += 1),
+        )
+        .set_parent(root)
+        .id();
+    let child = app
+        .world_mut()
+        .spawn(Blink::new(10, 0.2).with_low(0.5))
+        .set_parent(root)
+        .id();
+    let grandchild = app
+        .world_mut()
+        .spawn(OpacityTrack::new([(0.0, 0.2), (1.0, 1.0)]))
+        .set_parent(child)
+        .id();
+    common::step(&mut app, 0.05);
+    assert_eq!(app.world().get::<Opacity>(child), Some(&Opacity(0.5)));
+    app.world_mut().commands().fade_out_subtree(root, 1.0);
+    app.world_mut().flush();
+    for entity in [root, child, grandchild] {
+        assert!(app.world().get::<Blink>(entity).is_none());
+        assert!(app.world().get::<OpacityTrack>(entity).is_none());
+    }
+    assert!(app.world().get::<FadeOut>(root).is_some());
+    assert!(app.world().get::<FadeOut>(fading).is_some());
+    // Descendants keep their opacity and fade along with the root.
+    // A running blink would be back at `1.0` by now.
+    common::step(&mut app, 0.1);
+    assert_eq!(app.world().get::<Opacity>(child), Some(&Opacity(0.5)));
+    assert!((app.world().get::<Opacity>(root).unwrap().0 - 0.9).abs() < 1e-5);
+    common::step(&mut app, 1.0);
+    assert!(app.world().get_entity(grandchild).is_err());
+    assert!(app.world().get_entity(fading).is_err());
+    assert_eq!(app.world().resource::<Completions>().0, 1);
+}
+
+#[test]
+fn fade_completed() {
+    let mut app = common::app();
+    let fade_in = app.world_mut().spawn(FadeIn::new(0.5)).id();
+    let fade_out = app.world_mut().spawn(FadeOut::new(1.0)).id();
+    let completed = |app: &mut bevy::app::App| {
+        app.world_mut()
+            .resource_mut::<Events<FadeCompleted>>()
+            .drain()
+            .collect::<Vec<_>>()
+    };
+    common::step(&mut app, 0.25);
+    assert_eq!(completed(&mut app), []);
+    common::step(&mut app, 0.5);
+    assert_eq!(completed(&mut app), [FadeCompleted::In(fade_in)]);
+    common::step(&mut app, 0.5);
+    assert_eq!(completed(&mut app), [FadeCompleted::Out(fade_out)]);
+    assert!(app.world().get_entity(fade_out).is_err());
+}
+
+#[test]
+fn time_to_target() {
+    let mut opacity = Opacity(0.5);
+    let mut fade = FadeIn::new(2.0);
+    assert_eq!(fade.time_to_target(&opacity), Some(1.0));
+    fade.step(&mut opacity, 0.5);
+    assert_eq!(fade.time_to_target(&opacity), Some(0.5));
+    opacity.0 = 1.5;
+    assert_eq!(fade.time_to_target(&opacity), Some(0.0));
+    assert_eq!(FadeIn::new(f32::INFINITY).time_to_target(&opacity), None);
+
+    let mut opacity = Opacity::FULL;
+    let mut fade = FadeOut::new(1.0).with_target(0.5);
+    assert_eq!(fade.time_to_target(&opacity), Some(1.0));
+    fade.step(&mut opacity, 0.5);
+    assert_eq!(fade.time_to_target(&opacity), Some(0.5));
+
+    // A target of `1.0` is already reached and completes on the next step,
+    // without raising opacity.
+    for mut fade in [
+        FadeOut::new(1.0).with_target(1.0),
+        FadeOut::new(1.0)
+            .with_target(1.0)
+            .with_fade_curve(FadeCurve::EaseIn),
+    ] {
+        let mut opacity = Opacity(0.5);
+        assert_eq!(fade.time_to_target(&opacity), Some(0.0));
+        assert!(fade.step(&mut opacity, 0.1));
+        assert_eq!(opacity, Opacity(0.5));
+    }
+
+    let mut opacity = Opacity::FULL;
+    let mut fade = FadeOut::new(1.0).with_fade_curve(FadeCurve::EaseIn);
+    fade.step(&mut opacity, 0.25);
+    assert_eq!(fade.time_to_target(&opacity), Some(0.75));
+    assert_eq!(FadeOut::new(0.).time_to_target(&opacity), Some(0.));
+}
+
+#[test]
+fn negative_time() {
+    for time in [-1.0, f32::NAN] {
+        let mut opacity = Opacity(0.5);
+        assert!(FadeIn::new(time).step(&mut opacity, 0.1));
+        assert_eq!(opacity, Opacity::FULL);
+        assert!(FadeOut::new(time).with_target(0.2).step(&mut opacity, 0.1));
+        assert_eq!(opacity, Opacity(0.2));
+        assert_eq!(FadeIn::new(time).time_to_target(&opacity), Some(0.));
+    }
+}
+
+#[test]
+fn curve_secs() {
+    // Reaches `1.0` after half a second regardless of duration.
+    let curve = |t: f32, _: f32| (t * 2.).min(1.);
+    for duration in [1.0, 4.0] {
+        let mut opacity = Opacity::INVISIBLE;
+        let mut fade = FadeIn::new(duration).with_curve_secs(curve);
+        assert!(!fade.step(&mut opacity, 0.25));
+        assert_eq!(opacity, Opacity(0.5));
+        assert!(fade.step(&mut opacity, 0.25));
+        assert_eq!(opacity, Opacity::FULL);
+
+        let mut opacity = Opacity::FULL;
+        let mut fade = FadeOut::new(duration).with_curve_secs(curve);
+        assert!(!fade.step(&mut opacity, 0.25));
+        assert_eq!(opacity, Opacity(0.5));
+        assert!(fade.step(&mut opacity, 0.25));
+        assert_eq!(opacity, Opacity::INVISIBLE);
+    }
+    // Waits, then fades during the last half second, ending at the duration.
+    let curve = |t: f32, d: f32| ((t - d + 0.5) / 0.5).clamp(0., 1.);
+    let mut opacity = Opacity::INVISIBLE;
+    let mut fade = FadeIn::new(2.0).with_curve_secs(curve);
+    assert!(!fade.step(&mut opacity, 1.0));
+    assert_eq!(opacity, Opacity::INVISIBLE);
+    assert_eq!(fade.time_to_target(&opacity), Some(1.0));
+    assert!(!fade.step(&mut opacity, 0.75));
+    assert_eq!(opacity, Opacity(0.5));
+    assert_eq!(fade.time_to_target(&opacity), Some(0.25));
+    assert!(fade.step(&mut opacity, 0.25));
+    assert_eq!(opacity, Opacity::FULL);
+    // A normalized curve stretches with the duration instead.
+    let mut opacity = Opacity::INVISIBLE;
+    let mut fade = FadeIn::new(4.0).with_curve(|x| (x * 2.).min(1.));
+    fade.step(&mut opacity, 0.25);
+    assert_eq!(opacity, Opacity(0.125));
+}
+
+#[test]
+fn despawn_modes() {
+    let cases = [
+        (DespawnMode::Recursive, false, false),
+        (DespawnMode::DescendantsOnly, true, false),
+        (DespawnMode::SelfOnly, false, true),
+    ];
+    for (mode, root_remains, child_remains) in cases {
+        let mut app = common::app();
+        app.insert_resource(FadeConfig {
+            despawn_mode: mode,
+            ..Default::default()
+        });
+        let fade_out = app.world_mut().spawn(FadeOut::new(1.0)).id();
+        let fade_in = app.world_mut().spawn(FadeIn::new(1.0).despawning()).id();
+        let children = [fade_out, fade_in].map(|parent| {
+            app.world_mut()
+                .spawn(Sprite::default())
+                .set_parent(parent)
+                .id()
+        });
+        common::step(&mut app, 1.5);
+        for (root, child) in [fade_out, fade_in].into_iter().zip(children) {
+            assert_eq!(app.world().get_entity(root).is_ok(), root_remains);
+            assert_eq!(app.world().get_entity(child).is_ok(), child_remains);
+        }
+        if root_remains {
+            assert!(app.world().get::<FadeOut>(fade_out).is_none());
+            assert!(app.world().get::<FadeIn>(fade_in).is_none());
+        }
+        if child_remains {
+            assert!(app.world().get::<Parent>(children[0]).is_none());
+        }
+    }
+}
+
+#[test]
+fn reverse_mid_fade() {
+    let mut app = common::app();
+    let entity = app.world_mut().spawn(FadeOut::new(1.0)).id();
+    common::step(&mut app, 0.5);
+    assert_eq!(app.world().get::<Opacity>(entity), Some(&Opacity(0.5)));
+    // Required components never replace the existing `Opacity`.
+    app.world_mut()
+        .entity_mut(entity)
+        .remove::<FadeOut>()
+        .insert(FadeIn::new(1.0));
+    assert_eq!(app.world().get::<Opacity>(entity), Some(&Opacity(0.5)));
+    common::step(&mut app, 0.25);
+    assert_eq!(app.world().get::<Opacity>(entity), Some(&Opacity(0.75)));
+
+    app.world_mut()
+        .entity_mut(entity)
+        .remove::<FadeIn>()
+        .insert(FadeOut::new(1.0));
+    assert_eq!(app.world().get::<Opacity>(entity), Some(&Opacity(0.75)));
+    common::step(&mut app, 0.25);
+    assert_eq!(app.world().get::<Opacity>(entity), Some(&Opacity(0.5)));
+}
+
+#[test]
+fn opacity_floor() {
+    let mut app = common::app();
+    let entity = app
+        .world_mut()
+        .spawn((FadeOut::new(1.0), OpacityFloor(0.2), Sprite::default()))
+        .id();
+    let child = app
+        .world_mut()
+        .spawn(Sprite::default())
+        .set_parent(entity)
+        .id();
+    common::step(&mut app, 0.5);
+    assert_eq!(
+        app.world().get::<Sprite>(entity).unwrap().color.alpha(),
+        0.5
+    );
+    common::step(&mut app, 1.0);
+    assert_eq!(
+        app.world().get::<Opacity>(entity),
+        Some(&Opacity::INVISIBLE)
+    );
+    assert_eq!(
+        app.world().get::<Sprite>(entity).unwrap().color.alpha(),
+        0.2
+    );
+    assert_eq!(app.world().get::<Sprite>(child).unwrap().color.alpha(), 0.2);
+    // Fading out stops at the floor instead of despawning.
+    common::step(&mut app, 0.);
+    assert!(app.world().get::<FadeOut>(entity).is_none());
+    assert_eq!(
+        app.world().get::<Sprite>(entity).unwrap().color.alpha(),
+        0.2
+    );
+}
+
+#[test]
+fn fading_group() {
+    let mut app = common::app();
+    let root = app.world_mut().commands().spawn_fading_group(
+        Transform::default(),
+        (0..8).map(|i| (Sprite::default(), Transform::from_xyz(i as f32, 0., 0.))),
+        1.0,
+    );
+    app.world_mut().flush();
+    let children = app.world().get::<Children>(root).unwrap().to_vec();
+    assert_eq!(children.len(), 8);
+    common::step(&mut app, 0.5);
+    for child in &children {
+        assert_eq!(
+            app.world().get::<Sprite>(*child).unwrap().color.alpha(),
+            0.5
+        );
+    }
+    common::step(&mut app, 0.5);
+    assert!(app.world().get_entity(root).is_err());
+    for child in children {
+        assert!(app.world().get_entity(child).is_err());
+    }
+}
+
+#[test]
+fn fade_in_from_current() {
+    let mut app = common::app();
+    let entity = app.world_mut().spawn(Opacity(0.6)).id();
+    let partial = app.world_mut().spawn(Opacity(0.6)).id();
+    app.world_mut()
+        .entity_mut(entity)
+        .insert(FadeIn::from_current(1.0));
+    app.world_mut().entity_mut(partial).insert(FadeIn::new(1.0));
+    common::step(&mut app, 0.2);
+    assert!((app.world().get::<Opacity>(entity).unwrap().0 - 0.68).abs() < 1e-5);
+    assert!((app.world().get::<Opacity>(partial).unwrap().0 - 0.8).abs() < 1e-5);
+    common::step(&mut app, 0.3);
+    assert!((app.world().get::<Opacity>(entity).unwrap().0 - 0.8).abs() < 1e-5);
+    assert!(app.world().get::<FadeIn>(partial).is_none());
+    common::step(&mut app, 0.5);
+    assert_eq!(app.world().get::<Opacity>(entity), Some(&Opacity::FULL));
+    assert!(app.world().get::<FadeIn>(entity).is_none());
+}
+
+#[test]
+fn fade_in_from_current_curved() {
+    for fade in [
+        FadeIn::from_current(1.0).with_fade_curve(FadeCurve::EaseIn),
+        FadeIn::from_current(1.0).with_curve(|x| x * x),
+        FadeIn::from_current(1.0).with_ease(EaseFunction::QuadraticIn),
+    ] {
+        let mut opacity = Opacity(0.6);
+        let mut fade = fade;
+        assert!(!fade.step(&mut opacity, 0.5));
+        assert!((opacity.0 - 0.7).abs() < 1e-5);
+        assert_eq!(fade.time_to_target(&opacity), Some(0.5));
+        assert!(!fade.step(&mut opacity, 0.25));
+        assert!(fade.step(&mut opacity, 0.25));
+        assert_eq!(opacity, Opacity::FULL);
+    }
+}
+
+#[test]
+fn toast() {
+    let mut app = common::app();
+    let entity = app
+        .world_mut()
+        .spawn((Toast::new(1.0, 2.0, 1.0), Sprite::default()))
+        .id();
+    let phase = |app: &App| app.world().get::<Toast>(entity).unwrap().phase();
+    common::step(&mut app, 0.5);
+    assert_eq!(phase(&app), ToastPhase::FadeIn);
+    assert_eq!(
+        app.world().get::<Sprite>(entity).unwrap().color.alpha(),
+        0.5
+    );
+    common::step(&mut app, 0.5);
+    assert_eq!(phase(&app), ToastPhase::Hold);
+    assert_eq!(app.world().get::<Opacity>(entity), Some(&Opacity::FULL));
+    common::step(&mut app, 1.0);
+    assert_eq!(phase(&app), ToastPhase::Hold);
+    common::step(&mut app, 1.0);
+    assert_eq!(phase(&app), ToastPhase::FadeOut);
+    assert_eq!(app.world().get::<Opacity>(entity), Some(&Opacity::FULL));
+    common::step(&mut app, 0.5);
+    assert_eq!(
+        app.world().get::<Sprite>(entity).unwrap().color.alpha(),
+        0.5
+    );
+    common::step(&mut app, 0.5);
+    assert!(app.world().get_entity(entity).is_err());
+    let events = app.world().resource::<Events<FadeCompleted>>();
+    let mut reader = events.get_cursor();
+    assert_eq!(
+        reader.read(events).copied().collect::<Vec<_>>(),
+        vec![FadeCompleted::Out(entity)]
+    );
+}
+
+#[test]
+fn set_current_keeps_fade() {
+    let mut app = common::app();
+    let entity = app.world_mut().spawn(FadeIn::new(1.0)).id();
+    app.world_mut().get_mut::<Opacity>(entity).unwrap().0 = 0.;
+    common::step(&mut app, 0.25);
+    app.world_mut()
+        .get_mut::<Opacity>(entity)
+        .unwrap()
+        .set_current(0.5);
+    common::step(&mut app, 0.25);
+    assert_eq!(app.world().get::<Opacity>(entity), Some(&Opacity(0.75)));
+    assert!(app.world().entity(entity).contains::<FadeIn>());
+    common::step(&mut app, 0.25);
+    assert_eq!(app.world().get::<Opacity>(entity), Some(&Opacity::FULL));
+    assert!(!app.world().entity(entity).contains::<FadeIn>());
+
+    let entity = app.world_mut().spawn(FadeOut::new(1.0)).id();
+    common::step(&mut app, 0.25);
+    assert_eq!(app.world().get::<Opacity>(entity), Some(&Opacity(0.75)));
+    app.world_mut()
+        .get_mut::<Opacity>(entity)
+        .unwrap()
+        .set_current(0.5);
+    common::step(&mut app, 0.25);
+    assert_eq!(app.world().get::<Opacity>(entity), Some(&Opacity(0.25)));
+    assert!(app.world().entity(entity).contains::<FadeOut>());
+    common::step(&mut app, 0.25);
+    assert!(app.world().get_entity(entity).is_err());
+}
+
+#[test]
+fn set_current_despawns() {
+    let mut app = common::app();
+    let entity = app
+        .world_mut()
+        .spawn((FadeOut::new(10.0), Sprite::default()))
+        .id();
+    common::step(&mut app, 1.0);
+    assert!(app.world().get_entity(entity).is_ok());
+    app.world_mut()
+        .get_mut::<Opacity>(entity)
+        .unwrap()
+        .set_current(0.);
+    common::step(&mut app, 0.1);
+    assert!(app.world().get_entity(entity).is_err());
+}
@@ -0,0 +1,67 @@
+use bevy::{
+    app::Last,
+    prelude::{Changed, Query, ResMut, Resource},
+};
+use bevy_mod_opacity::{Opacity, OpacityFollow};
+
+mod common;
+
+#[test]
+fn converges() {
+    let mut app = common::app();
+    let entity = app
+        .world_mut()
+        .spawn((Opacity::INVISIBLE, OpacityFollow::new(1.0, 10.0)))
+        .id();
+    for _ in 0..120 {
+        common::step(&mut app, 1. / 60.);
+    }
+    let opacity = app.world().get::<Opacity>(entity).unwrap().0;
+    assert!((opacity - 1.0).abs() < 1e-3);
+}
+
+#[test]
+fn retarget_keeps_velocity() {
+    let dt = 1. / 60.;
+    let mut opacity = Opacity::INVISIBLE;
+    let mut follow = OpacityFollow::new(1.0, 8.0);
+    let mut max_change = 0f32;
+    let mut last = follow.velocity();
+    for frame in 0..240 {
+        if frame % 20 == 10 {
+            follow.set_target(if follow.target > 0.5 { 0.2 } else { 0.9 });
+        }
+        follow.step(&mut opacity, dt);
+        max_change = max_change.max((follow.velocity() - last).abs());
+        last = follow.velocity();
+    }
+    // Recomputing speed from distance on retarget would jump by about `frequency * 0.7`.
+    assert!(max_change < 8.0 * 0.7 * 0.25);
+    assert!((0.0..=1.0).contains(&opacity.0));
+}
+
+#[derive(Debug, Default, Resource)]
+pub struct OpacityChanges(usize);
+
+#[test]
+fn settled_unchanged() {
+    let mut app = common::app();
+    app.init_resource::<OpacityChanges>().add_systems(
+        Last,
+        |query: Query<(), Changed<Opacity>>, mut changes: ResMut<OpacityChanges>| {
+            changes.0 += query.iter().count();
+        },
+    );
+    let entity = app
+        .world_mut()
+        .spawn((Opacity::INVISIBLE, OpacityFollow::new(1.0, 10.0)))
+        .id();
+    for _ in 0..300 {
+        common::step(&mut app, 1. / 60.);
+    }
+    assert_eq!(app.world().get::<Opacity>(entity), Some(&Opacity::FULL));
+    let changes = app.world().resource::<OpacityChanges>().0;
+    common::step(&mut app, 1. / 60.);
+    common::step(&mut app, 1. / 60.);
+    assert_eq!(app.world().resource::<OpacityChanges>().0, changes);
+}
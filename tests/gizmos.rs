@@ -0,0 +1,74 @@
+use bevy::{
+    app::PostUpdate, color::Alpha, color::Color, gizmos::aabb::ShowAabbGizmo, prelude::Sprite,
+};
+use bevy_mod_opacity::{BaseAlpha, FadeOut, OpacityExtension};
+
+mod common;
+
+#[test]
+fn aabb_gizmo() {
+    let mut app = common::app();
+    app.register_opacity_gizmos();
+    let colored = app
+        .world_mut()
+        .spawn((
+            ShowAabbGizmo {
+                color: Some(Color::srgba(1., 0., 0., 0.5)),
+            },
+            FadeOut::new(1.0),
+        ))
+        .id();
+    let default = app
+        .world_mut()
+        .spawn((ShowAabbGizmo { color: None }, FadeOut::new(1.0)))
+        .id();
+    common::step(&mut app, 0.5);
+    let gizmo = app.world().get::<ShowAabbGizmo>(colored).unwrap();
+    assert_eq!(gizmo.color.map(|x| x.alpha()), Some(0.25));
+    assert_eq!(
+        app.world().get::<BaseAlpha<ShowAabbGizmo>>(colored),
+        Some(&BaseAlpha::new(0.5))
+    );
+    assert_eq!(
+        app.world().get::<ShowAabbGizmo>(default).unwrap().color,
+        None
+    );
+}
+
+#[test]
+fn aabb_gizmo_on_sprite() {
+    let mut app = common::app();
+    app.register_opacity_gizmos();
+    let entity = app
+        .world_mut()
+        .spawn((
+            Sprite::from_color(Color::srgba(1., 1., 1., 0.8), Default::default()),
+            ShowAabbGizmo {
+                color: Some(Color::srgba(1., 0., 0., 0.5)),
+            },
+            FadeOut::new(1.0),
+        ))
+        .id();
+    common::step(&mut app, 0.5);
+    assert_eq!(
+        app.world().get::<Sprite>(entity).unwrap().color.alpha(),
+        0.4
+    );
+    assert_eq!(
+        app.world()
+            .get::<ShowAabbGizmo>(entity)
+            .unwrap()
+            .color
+            .map(|x| x.alpha()),
+        Some(0.25)
+    );
+}
+
+#[test]
+fn registered_twice() {
+    let mut app = common::app();
+    app.register_opacity_gizmos();
+    let systems = app.get_schedule(PostUpdate).unwrap().systems_len();
+    app.register_opacity_gizmos();
+    assert_eq!(app.get_schedule(PostUpdate).unwrap().systems_len(), systems);
+}
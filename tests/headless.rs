@@ -0,0 +1,115 @@
+use bevy::{
+    app::{App, FixedUpdate},
+    asset::{AssetPlugin, Assets},
+    pbr::StandardMaterial,
+    prelude::{IntoSystemConfigs, MinimalPlugins, Query, ResMut, Resource},
+    sprite::ColorMaterial,
+    time::{Fixed, Time, TimeUpdateStrategy, Virtual},
+};
+use bevy_mod_opacity::{
+    FadeOut, Opacity, OpacityExtension, OpacityPlugin, OpacitySet, OpacitySettings, RenderOpacity,
+};
+use std::time::Duration;
+
+mod common;
+
+#[test]
+fn without_render_plugin() {
+    let mut app = App::new();
+    app.add_plugins((MinimalPlugins, AssetPlugin::default()))
+        .init_resource::<Assets<ColorMaterial>>()
+        .init_resource::<Assets<StandardMaterial>>()
+        .insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_millis(
+            250,
+        )))
+        .add_plugins(OpacityPlugin);
+    app.finish();
+    app.cleanup();
+    let entity = app.world_mut().spawn(FadeOut::new(1.)).id();
+    app.update();
+    app.update();
+    app.update();
+    let opacity = app.world().get::<Opacity>(entity).unwrap().0;
+    assert!(opacity < 1.);
+    assert!(app.world().resource::<Time<Virtual>>().elapsed_secs() > 0.);
+}
+
+#[test]
+fn added_twice() {
+    let mut app = common::app();
+    app.add_plugins(OpacityPlugin)
+        .register_opacity_component::<RenderOpacity>();
+    let entity = app
+        .world_mut()
+        .spawn((FadeOut::new(1.), RenderOpacity::default()))
+        .id();
+    common::step(&mut app, 0.25);
+    assert_eq!(app.world().get::<Opacity>(entity), Some(&Opacity(0.75)));
+    assert_eq!(
+        app.world().get::<RenderOpacity>(entity),
+        Some(&RenderOpacity(0.75))
+    );
+}
+
+#[derive(Debug, Default, Resource)]
+pub struct FixedSteps(u32);
+
+#[derive(Debug, Default, Resource)]
+pub struct PostFadeOpacity(f32);
+
+#[test]
+fn fixed_interpolation() {
+    let mut app = App::new();
+    app.add_plugins((MinimalPlugins, AssetPlugin::default()))
+        .init_resource::<Assets<ColorMaterial>>()
+        .init_resource::<Assets<StandardMaterial>>()
+        .insert_resource(Time::<Fixed>::from_seconds(0.25))
+        .insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_millis(
+            250,
+        )))
+        .init_resource::<FixedSteps>()
+        .init_resource::<PostFadeOpacity>()
+        .add_systems(FixedUpdate, |mut steps: ResMut<FixedSteps>| steps.0 += 1)
+        .add_systems(
+            FixedUpdate,
+            (|query: Query<&Opacity>, mut post: ResMut<PostFadeOpacity>| {
+                post.0 = query.single().0;
+            })
+            .in_set(OpacitySet::PostFade),
+        )
+        .add_plugins(OpacityPlugin::builder().fixed_interpolation(true));
+    app.finish();
+    app.cleanup();
+    let entity = app.world_mut().spawn(FadeOut::new(2.0)).id();
+    for _ in 0..5 {
+        app.update();
+        let steps = app.world().resource::<FixedSteps>().0;
+        let opacity = app.world().get::<Opacity>(entity).unwrap().0;
+        assert_eq!(opacity, 1.0 - 0.125 * steps as f32);
+        // `PostFade` runs after the fixed step drivers.
+        if steps > 0 {
+            assert_eq!(app.world().resource::<PostFadeOpacity>().0, opacity);
+        }
+    }
+    assert!(app.world().resource::<FixedSteps>().0 > 0);
+}
+
+#[test]
+fn plugin_settings() {
+    // Settings apply even if the plugin was already added, for example by a third party plugin.
+    let mut app = common::app();
+    app.add_plugins(
+        OpacityPlugin::builder()
+            .fixed_interpolation(true)
+            .with_smoothing(0.5),
+    );
+    assert_eq!(
+        *app.world().resource::<OpacitySettings>(),
+        OpacitySettings {
+            fixed: true,
+            smoothing: Some(0.5),
+        }
+    );
+    app.add_plugins(OpacityPlugin);
+    assert!(app.world().resource::<OpacitySettings>().fixed);
+}
@@ -0,0 +1,274 @@
+use bevy::{
+    color::{Alpha, Color},
+    ecs::system::RunSystemOnce,
+    math::Vec2,
+    prelude::{BuildChildren, Parent, Sprite},
+};
+use bevy_mod_opacity::{
+    apply_opacity_now, calculate_inherited_opacity, FadeOut, InheritedOpacity, Opacity,
+    OpacityCommands, OpacityDisabled, OpacityMap, OpacityOverride, OpacityReader, SecondaryOpacity,
+};
+
+mod common;
+
+#[test]
+fn delayed_child() {
+    let mut app = common::app();
+    let root = app.world_mut().spawn(Opacity(0.5)).id();
+    app.update();
+    app.update();
+    let child = app
+        .world_mut()
+        .spawn(Sprite::default())
+        .set_parent(root)
+        .id();
+    app.update();
+    let sprite = app.world().get::<Sprite>(child).unwrap();
+    assert_eq!(sprite.color.alpha(), 0.5);
+}
+
+#[test]
+fn late_sprite() {
+    let mut app = common::app();
+    let root = app.world_mut().spawn(Opacity(0.5)).id();
+    let child = app.world_mut().spawn_empty().set_parent(root).id();
+    app.update();
+    app.update();
+    app.world_mut()
+        .entity_mut(child)
+        .insert(Sprite::from_color(Color::srgba(1., 1., 1., 0.8), Vec2::ONE));
+    app.update();
+    let sprite = app.world().get::<Sprite>(child).unwrap();
+    assert_eq!(sprite.color.alpha(), 0.4);
+}
+
+#[test]
+fn apply_now() {
+    let mut app = common::app();
+    let root = app
+        .world_mut()
+        .spawn((Opacity::INVISIBLE, Sprite::default()))
+        .id();
+    let child = app
+        .world_mut()
+        .spawn(Sprite::default())
+        .set_parent(root)
+        .id();
+    apply_opacity_now(app.world_mut());
+    assert_eq!(app.world().get::<Sprite>(root).unwrap().color.alpha(), 0.);
+    assert_eq!(app.world().get::<Sprite>(child).unwrap().color.alpha(), 0.);
+
+    *app.world_mut().get_mut::<Opacity>(root).unwrap() = Opacity(0.5);
+    app.world_mut().commands().apply_opacity_now();
+    app.world_mut().flush();
+    assert_eq!(app.world().get::<Sprite>(child).unwrap().color.alpha(), 0.5);
+}
+
+#[test]
+fn opacity_override() {
+    let mut app = common::app();
+    let root = app.world_mut().spawn(Opacity::FULL).id();
+    let pinned = app
+        .world_mut()
+        .spawn((OpacityOverride(0.25), Sprite::default()))
+        .set_parent(root)
+        .id();
+    let child = app
+        .world_mut()
+        .spawn(Sprite::default())
+        .set_parent(pinned)
+        .id();
+    app.update();
+    assert_eq!(
+        app.world().get::<Sprite>(pinned).unwrap().color.alpha(),
+        0.25
+    );
+    assert_eq!(
+        app.world().get::<Sprite>(child).unwrap().color.alpha(),
+        0.25
+    );
+
+    *app.world_mut().get_mut::<Opacity>(root).unwrap() = Opacity::INVISIBLE;
+    *app.world_mut().get_mut::<OpacityOverride>(pinned).unwrap() = OpacityOverride(1.0);
+    app.update();
+    assert_eq!(
+        app.world().get::<Sprite>(pinned).unwrap().color.alpha(),
+        1.0
+    );
+    assert_eq!(app.world().get::<Sprite>(child).unwrap().color.alpha(), 1.0);
+}
+
+#[test]
+fn inherited_opacity() {
+    let mut app = common::app();
+    let root = app.world_mut().spawn(Opacity(0.5)).id();
+    let middle = app.world_mut().spawn(Opacity(0.5)).set_parent(root).id();
+    let leaf = app.world_mut().spawn_empty().set_parent(middle).id();
+    let other = app.world_mut().spawn_empty().id();
+    assert_eq!(calculate_inherited_opacity(app.world(), root), 0.5);
+    assert_eq!(calculate_inherited_opacity(app.world(), middle), 0.25);
+    assert_eq!(calculate_inherited_opacity(app.world(), leaf), 0.25);
+    assert_eq!(calculate_inherited_opacity(app.world(), other), 1.0);
+
+    let result = app
+        .world_mut()
+        .run_system_once(move |opacity: InheritedOpacity| opacity.get(leaf))
+        .unwrap();
+    assert_eq!(result, 0.25);
+}
+
+#[test]
+fn root_of() {
+    let mut app = common::app();
+    let outer = app.world_mut().spawn(Opacity(0.5)).id();
+    let inner = app.world_mut().spawn(Opacity(0.5)).set_parent(outer).id();
+    let leaf = app.world_mut().spawn_empty().set_parent(inner).id();
+    let pinned = app
+        .world_mut()
+        .spawn(OpacityOverride(1.0))
+        .set_parent(inner)
+        .id();
+    let pinned_leaf = app.world_mut().spawn_empty().set_parent(pinned).id();
+    let other = app.world_mut().spawn_empty().id();
+    app.update();
+    let map = app.world().resource::<OpacityMap>();
+    assert_eq!(map.root_of(outer), Some(outer));
+    assert_eq!(map.root_of(inner), Some(outer));
+    assert_eq!(map.root_of(leaf), Some(outer));
+    assert_eq!(map.get(leaf), Some(0.25));
+    assert_eq!(map.root_of(pinned), Some(pinned));
+    assert_eq!(map.root_of(pinned_leaf), Some(pinned));
+    assert_eq!(map.get(pinned_leaf), Some(1.0));
+    assert_eq!(map.root_of(other), None);
+    assert_eq!(map.get(other), None);
+}
+
+#[test]
+fn opacity_reader() {
+    let mut app = common::app();
+    let root = app.world_mut().spawn(FadeOut::new(1.0)).id();
+    let child = app.world_mut().spawn(Opacity(0.5)).set_parent(root).id();
+    let other = app.world_mut().spawn(Opacity(0.5)).id();
+    common::step(&mut app, 0.5);
+    let result = app
+        .world_mut()
+        .run_system_once(move |reader: OpacityReader| {
+            (
+                reader.opacity_of(child),
+                reader.root_of(child),
+                reader.is_fading(child),
+                reader.is_fading(other),
+            )
+        })
+        .unwrap();
+    assert_eq!(result, (Some(0.25), Some(root), true, false));
+}
+
+#[test]
+fn nested_roots() {
+    let mut app = common::app();
+    // Spawn from the leaf up so the middle level is visited before its parent.
+    let leaf = app.world_mut().spawn(Opacity(0.25)).id();
+    let middle = app.world_mut().spawn(Opacity(0.5)).id();
+    let outer = app.world_mut().spawn(Opacity(0.5)).id();
+    app.world_mut().entity_mut(leaf).set_parent(middle);
+    app.world_mut().entity_mut(middle).set_parent(outer);
+    let child = app.world_mut().spawn_empty().set_parent(leaf).id();
+    app.update();
+    let map = app.world().resource::<OpacityMap>();
+    assert_eq!(map.get(outer), Some(0.5));
+    assert_eq!(map.get(middle), Some(0.25));
+    assert_eq!(map.get(leaf), Some(0.0625));
+    assert_eq!(map.get(child), Some(0.0625));
+    assert!(map.is_root(outer));
+    assert!(!map.is_root(middle));
+    assert!(!map.is_root(leaf));
+    assert!(!map.is_root(child));
+    assert_eq!(map.root_of(child), Some(outer));
+}
+
+#[test]
+fn secondary_opacity() {
+    let mut app = common::app();
+    let root = app
+        .world_mut()
+        .spawn((Opacity(0.5), SecondaryOpacity(0.5), Sprite::default()))
+        .id();
+    let child = app
+        .world_mut()
+        .spawn(Sprite::default())
+        .set_parent(root)
+        .id();
+    app.update();
+    assert_eq!(app.world().resource::<OpacityMap>().get(root), Some(0.25));
+    let sprite = app.world().get::<Sprite>(child).unwrap();
+    assert_eq!(sprite.color.alpha(), 0.25);
+    assert_eq!(calculate_inherited_opacity(app.world(), child), 0.25);
+
+    // Each layer is driven independently.
+    app.world_mut().get_mut::<SecondaryOpacity>(root).unwrap().0 = 1.;
+    app.update();
+    assert_eq!(app.world().resource::<OpacityMap>().get(child), Some(0.5));
+}
+
+#[test]
+fn opacity_disabled() {
+    let mut app = common::app();
+    let root = app
+        .world_mut()
+        .spawn((Opacity(0.5), SecondaryOpacity(0.5), Sprite::default()))
+        .id();
+    let middle = app.world_mut().spawn(Opacity(0.5)).set_parent(root).id();
+    let child = app
+        .world_mut()
+        .spawn(Sprite::default())
+        .set_parent(middle)
+        .id();
+    app.update();
+    assert_eq!(
+        app.world().get::<Sprite>(child).unwrap().color.alpha(),
+        0.125
+    );
+
+    app.world_mut().commands().set_opacity_enabled(root, false);
+    app.update();
+    assert!(app.world().entity(root).contains::<OpacityDisabled>());
+    assert_eq!(app.world().get::<Opacity>(root), Some(&Opacity(0.5)));
+    assert_eq!(app.world().get::<Sprite>(root).unwrap().color.alpha(), 1.0);
+    assert_eq!(app.world().get::<Sprite>(child).unwrap().color.alpha(), 0.5);
+    assert_eq!(calculate_inherited_opacity(app.world(), child), 0.5);
+
+    app.world_mut().commands().set_opacity_enabled(root, true);
+    app.update();
+    assert_eq!(app.world().get::<Sprite>(root).unwrap().color.alpha(), 0.25);
+    assert_eq!(
+        app.world().get::<Sprite>(child).unwrap().color.alpha(),
+        0.125
+    );
+}
+
+#[test]
+fn cyclic_hierarchy() {
+    let mut app = common::app();
+    let a = app
+        .world_mut()
+        .spawn((Opacity(0.5), Sprite::default()))
+        .id();
+    let b = app.world_mut().spawn(Sprite::default()).set_parent(a).id();
+    // Manual manipulation can create a cycle bevy does not prevent.
+    app.world_mut().entity_mut(a).add_child(b);
+    app.world_mut().entity_mut(b).add_child(a);
+    assert_eq!(app.world().get::<Parent>(a).map(|x| x.get()), Some(b));
+    assert_eq!(app.world().get::<Parent>(b).map(|x| x.get()), Some(a));
+    app.update();
+    let map = app.world().resource::<OpacityMap>();
+    assert_eq!(map.get(a), Some(0.5));
+    assert_eq!(map.get(b), Some(0.5));
+    assert_eq!(calculate_inherited_opacity(app.world(), a), 0.5);
+    assert_eq!(calculate_inherited_opacity(app.world(), b), 0.5);
+    let inherited = app
+        .world_mut()
+        .run_system_once(move |inherited: InheritedOpacity| (inherited.get(a), inherited.get(b)))
+        .unwrap();
+    assert_eq!(inherited, (0.5, 0.5));
+}
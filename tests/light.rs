@@ -0,0 +1,150 @@
+use bevy::{
+    pbr::{DirectionalLight, PointLight},
+    prelude::BuildChildren,
+};
+use bevy_mod_opacity::{BaseIntensity, FadeLight, Opacity, OpacityMap, SceneFade};
+
+mod common;
+
+#[test]
+fn point_light() {
+    let mut app = common::app();
+    let entity = app
+        .world_mut()
+        .spawn((
+            Opacity(0.5),
+            PointLight {
+                intensity: 1000.,
+                ..Default::default()
+            },
+            FadeLight,
+        ))
+        .id();
+    common::step(&mut app, 0.);
+    assert_eq!(
+        app.world().get::<BaseIntensity>(entity),
+        Some(&BaseIntensity(1000.))
+    );
+    assert_eq!(
+        app.world().get::<PointLight>(entity).unwrap().intensity,
+        500.
+    );
+
+    app.world_mut().get_mut::<Opacity>(entity).unwrap().0 = 1.;
+    common::step(&mut app, 0.);
+    assert_eq!(
+        app.world().get::<PointLight>(entity).unwrap().intensity,
+        1000.
+    );
+}
+
+#[test]
+fn light_opt_in() {
+    let mut app = common::app();
+    let root = app.world_mut().spawn(Opacity(0.5)).id();
+    let entity = app
+        .world_mut()
+        .spawn(PointLight {
+            intensity: 1000.,
+            ..Default::default()
+        })
+        .set_parent(root)
+        .id();
+    common::step(&mut app, 0.);
+    assert_eq!(app.world().get::<BaseIntensity>(entity), None);
+    assert_eq!(
+        app.world().get::<PointLight>(entity).unwrap().intensity,
+        1000.
+    );
+}
+
+#[test]
+fn scene_fade() {
+    let mut app = common::app();
+    let sun = app
+        .world_mut()
+        .spawn(DirectionalLight {
+            illuminance: 10000.,
+            ..Default::default()
+        })
+        .id();
+    let scene = app
+        .world_mut()
+        .spawn((Opacity(0.25), SceneFade::new([sun])))
+        .id();
+    let lamp = app
+        .world_mut()
+        .spawn((
+            Opacity(0.5),
+            PointLight {
+                intensity: 1000.,
+                ..Default::default()
+            },
+        ))
+        .id();
+    app.world_mut()
+        .get_mut::<SceneFade>(scene)
+        .unwrap()
+        .lights
+        .push(lamp);
+    common::step(&mut app, 0.);
+    common::step(&mut app, 0.);
+    assert_eq!(app.world().get::<Opacity>(sun), None);
+    assert_eq!(app.world().resource::<OpacityMap>().get(sun), Some(0.25));
+    // The light's own opacity is kept and multiplied with the scene's.
+    assert_eq!(app.world().get::<Opacity>(lamp), Some(&Opacity(0.5)));
+    assert_eq!(app.world().get::<PointLight>(lamp).unwrap().intensity, 125.);
+    assert_eq!(
+        app.world()
+            .get::<DirectionalLight>(sun)
+            .unwrap()
+            .illuminance,
+        2500.
+    );
+
+    app.world_mut().get_mut::<Opacity>(scene).unwrap().0 = 0.;
+    common::step(&mut app, 0.);
+    assert_eq!(
+        app.world()
+            .get::<DirectionalLight>(sun)
+            .unwrap()
+            .illuminance,
+        0.
+    );
+}
+
+#[test]
+fn scene_fade_restore() {
+    let mut app = common::app();
+    let sun = app
+        .world_mut()
+        .spawn(DirectionalLight {
+            illuminance: 10000.,
+            ..Default::default()
+        })
+        .id();
+    let scene = app
+        .world_mut()
+        .spawn((Opacity(0.25), SceneFade::new([sun])))
+        .id();
+    common::step(&mut app, 0.);
+    common::step(&mut app, 0.);
+    assert_eq!(
+        app.world()
+            .get::<DirectionalLight>(sun)
+            .unwrap()
+            .illuminance,
+        2500.
+    );
+
+    app.world_mut().despawn(scene);
+    common::step(&mut app, 0.);
+    assert_eq!(app.world().resource::<OpacityMap>().get(sun), None);
+    assert_eq!(
+        app.world()
+            .get::<DirectionalLight>(sun)
+            .unwrap()
+            .illuminance,
+        10000.
+    );
+}
@@ -0,0 +1,427 @@
+use bevy::{
+    app::App,
+    asset::{Asset, Assets},
+    color::{Alpha, Color, LinearRgba},
+    pbr::{Material, MeshMaterial3d, StandardMaterial},
+    prelude::{AlphaMode, BuildChildren, Mesh, Visibility},
+    reflect::{Reflect, TypePath},
+    render::{render_resource::AsBindGroup, view::VisibilityPlugin},
+    sprite::{ColorMaterial, MeshMaterial2d},
+    ui::{MaterialNode, UiMaterial},
+};
+use bevy_mod_opacity::{
+    AutoIsolateMaterial, IsolatedMaterial, Opacity, OpacityExtension, PremultipliedAlpha,
+};
+
+mod common;
+
+#[test]
+fn masked_material() {
+    let mut app = common::app();
+    let handle = app
+        .world_mut()
+        .resource_mut::<Assets<StandardMaterial>>()
+        .add(StandardMaterial {
+            base_color: Color::WHITE,
+            alpha_mode: AlphaMode::Mask(0.5),
+            ..Default::default()
+        });
+    let entity = app
+        .world_mut()
+        .spawn((Opacity(0.25), MeshMaterial3d(handle.clone())))
+        .id();
+    app.update();
+    let material = app
+        .world()
+        .resource::<Assets<StandardMaterial>>()
+        .get(&handle)
+        .unwrap();
+    assert_eq!(material.alpha_mode, AlphaMode::Blend);
+    assert_eq!(material.base_color.alpha(), 0.25);
+
+    *app.world_mut().get_mut::<Opacity>(entity).unwrap() = Opacity::FULL;
+    app.update();
+    let material = app
+        .world()
+        .resource::<Assets<StandardMaterial>>()
+        .get(&handle)
+        .unwrap();
+    assert_eq!(material.alpha_mode, AlphaMode::Mask(0.5));
+    assert_eq!(material.base_color.alpha(), 1.0);
+}
+
+#[test]
+fn shared_masked_material() {
+    let mut app = common::app();
+    let handle = app
+        .world_mut()
+        .resource_mut::<Assets<StandardMaterial>>()
+        .add(StandardMaterial {
+            base_color: Color::WHITE,
+            alpha_mode: AlphaMode::Mask(0.5),
+            ..Default::default()
+        });
+    let faded = app
+        .world_mut()
+        .spawn((Opacity(0.25), MeshMaterial3d(handle.clone())))
+        .id();
+    app.world_mut()
+        .spawn((Opacity::FULL, MeshMaterial3d(handle.clone())));
+    for _ in 0..3 {
+        app.update();
+        let material = app
+            .world()
+            .resource::<Assets<StandardMaterial>>()
+            .get(&handle)
+            .unwrap();
+        assert_eq!(material.alpha_mode, AlphaMode::Blend);
+    }
+
+    *app.world_mut().get_mut::<Opacity>(faded).unwrap() = Opacity::FULL;
+    app.update();
+    let material = app
+        .world()
+        .resource::<Assets<StandardMaterial>>()
+        .get(&handle)
+        .unwrap();
+    assert_eq!(material.alpha_mode, AlphaMode::Mask(0.5));
+    assert_eq!(material.base_color.alpha(), 1.0);
+}
+
+#[test]
+fn auto_isolate() {
+    let mut app = common::app();
+    let shared = app
+        .world_mut()
+        .resource_mut::<Assets<StandardMaterial>>()
+        .add(StandardMaterial::default());
+    let root = app
+        .world_mut()
+        .spawn((Opacity::FULL, AutoIsolateMaterial))
+        .id();
+    let faded = app
+        .world_mut()
+        .spawn(MeshMaterial3d(shared.clone()))
+        .set_parent(root)
+        .id();
+    let other = app.world_mut().spawn(MeshMaterial3d(shared.clone())).id();
+    app.update();
+    assert_eq!(
+        app.world()
+            .get::<MeshMaterial3d<StandardMaterial>>(faded)
+            .unwrap()
+            .0,
+        shared
+    );
+
+    *app.world_mut().get_mut::<Opacity>(root).unwrap() = Opacity(0.5);
+    app.update();
+    let isolated = app
+        .world()
+        .get::<MeshMaterial3d<StandardMaterial>>(faded)
+        .unwrap()
+        .0
+        .clone();
+    assert_ne!(isolated, shared);
+    assert_eq!(
+        app.world().get::<IsolatedMaterial>(faded).unwrap().original,
+        shared
+    );
+    let assets = app.world().resource::<Assets<StandardMaterial>>();
+    assert_eq!(assets.get(&isolated).unwrap().base_color.alpha(), 0.5);
+    assert_eq!(assets.get(&shared).unwrap().base_color.alpha(), 1.0);
+    assert_eq!(
+        app.world()
+            .get::<MeshMaterial3d<StandardMaterial>>(other)
+            .unwrap()
+            .0,
+        shared
+    );
+
+    *app.world_mut().get_mut::<Opacity>(root).unwrap() = Opacity::FULL;
+    app.update();
+    assert_eq!(
+        app.world()
+            .get::<MeshMaterial3d<StandardMaterial>>(faded)
+            .unwrap()
+            .0,
+        shared
+    );
+    assert!(app.world().get::<IsolatedMaterial>(faded).is_none());
+
+    *app.world_mut().get_mut::<Opacity>(root).unwrap() = Opacity(0.25);
+    app.update();
+    let handle = app
+        .world()
+        .get::<MeshMaterial3d<StandardMaterial>>(faded)
+        .unwrap()
+        .0
+        .clone();
+    assert_ne!(handle, shared);
+    assert_ne!(handle, isolated);
+    let assets = app.world().resource::<Assets<StandardMaterial>>();
+    assert_eq!(assets.get(&handle).unwrap().base_color.alpha(), 0.25);
+    assert_eq!(assets.get(&shared).unwrap().base_color.alpha(), 1.0);
+}
+
+#[test]
+fn auto_isolate_nested() {
+    let mut app = common::app();
+    let shared = app
+        .world_mut()
+        .resource_mut::<Assets<StandardMaterial>>()
+        .add(StandardMaterial::default());
+    let root = app.world_mut().spawn(Opacity(0.5)).id();
+    let nested = app
+        .world_mut()
+        .spawn((Opacity::OPAQUE, AutoIsolateMaterial))
+        .set_parent(root)
+        .id();
+    let isolated = app
+        .world_mut()
+        .spawn(MeshMaterial3d(shared.clone()))
+        .set_parent(nested)
+        .id();
+    let unmarked = app
+        .world_mut()
+        .spawn(MeshMaterial3d(shared.clone()))
+        .set_parent(root)
+        .id();
+    app.update();
+    assert_eq!(
+        app.world()
+            .get::<IsolatedMaterial>(isolated)
+            .unwrap()
+            .original,
+        shared
+    );
+    assert!(app.world().get::<IsolatedMaterial>(unmarked).is_none());
+}
+
+#[test]
+fn hidden_subtree() {
+    let mut app = common::app();
+    app.init_resource::<Assets<Mesh>>()
+        .add_plugins(VisibilityPlugin);
+    app.finish();
+    let handle = app
+        .world_mut()
+        .resource_mut::<Assets<StandardMaterial>>()
+        .add(StandardMaterial::default());
+    let root = app
+        .world_mut()
+        .spawn((Opacity(0.5), Visibility::Hidden))
+        .id();
+    app.world_mut()
+        .spawn((MeshMaterial3d(handle.clone()), Visibility::Inherited))
+        .set_parent(root);
+    app.update();
+    let alpha = |app: &App| {
+        app.world()
+            .resource::<Assets<StandardMaterial>>()
+            .get(&handle)
+            .unwrap()
+            .base_color
+            .alpha()
+    };
+    assert_eq!(alpha(&app), 1.0);
+    *app.world_mut().get_mut::<Visibility>(root).unwrap() = Visibility::Visible;
+    app.update();
+    assert_eq!(alpha(&app), 0.5);
+}
+
+#[test]
+fn late_loaded_material() {
+    let mut app = common::app();
+    let handle = app
+        .world_mut()
+        .resource::<Assets<StandardMaterial>>()
+        .reserve_handle();
+    app.world_mut()
+        .spawn((Opacity(0.5), MeshMaterial3d(handle.clone())));
+    app.update();
+    app.update();
+    app.world_mut()
+        .resource_mut::<Assets<StandardMaterial>>()
+        .insert(&handle, StandardMaterial::default());
+    app.update();
+    let assets = app.world().resource::<Assets<StandardMaterial>>();
+    assert_eq!(assets.get(&handle).unwrap().base_color.alpha(), 0.5);
+}
+
+#[test]
+fn premultiplied_color_material() {
+    let mut app = common::app();
+    let mut materials = app.world_mut().resource_mut::<Assets<ColorMaterial>>();
+    let straight = materials.add(ColorMaterial::from_color(LinearRgba::new(1., 0.5, 0., 1.)));
+    let premultiplied = materials.add(ColorMaterial::from_color(LinearRgba::new(1., 0.5, 0., 1.)));
+    app.world_mut()
+        .spawn((Opacity(0.5), MeshMaterial2d(straight.clone())));
+    app.world_mut().spawn((
+        Opacity(0.5),
+        MeshMaterial2d(premultiplied.clone()),
+        PremultipliedAlpha,
+    ));
+    app.update();
+    let materials = app.world().resource::<Assets<ColorMaterial>>();
+    assert_eq!(
+        materials.get(&straight).unwrap().color.to_linear(),
+        LinearRgba::new(1., 0.5, 0., 0.5)
+    );
+    assert_eq!(
+        materials.get(&premultiplied).unwrap().color.to_linear(),
+        LinearRgba::new(0.5, 0.25, 0., 0.5)
+    );
+}
+
+#[test]
+fn modified_material() {
+    let mut app = common::app();
+    let color = app
+        .world_mut()
+        .resource_mut::<Assets<ColorMaterial>>()
+        .add(ColorMaterial::from_color(Color::WHITE));
+    let standard = app
+        .world_mut()
+        .resource_mut::<Assets<StandardMaterial>>()
+        .add(StandardMaterial::default());
+    app.world_mut().spawn((
+        Opacity(0.5),
+        MeshMaterial2d(color.clone()),
+        MeshMaterial3d(standard.clone()),
+    ));
+    app.update();
+    app.world_mut()
+        .resource_mut::<Assets<ColorMaterial>>()
+        .get_mut(&color)
+        .unwrap()
+        .color = Color::srgba(1., 0., 0., 0.8);
+    app.world_mut()
+        .resource_mut::<Assets<StandardMaterial>>()
+        .get_mut(&standard)
+        .unwrap()
+        .base_color = Color::srgba(1., 0., 0., 0.6);
+    app.update();
+    let material = app
+        .world()
+        .resource::<Assets<ColorMaterial>>()
+        .get(&color)
+        .unwrap();
+    assert_eq!(material.color, Color::srgba(1., 0., 0., 0.4));
+    let material = app
+        .world()
+        .resource::<Assets<StandardMaterial>>()
+        .get(&standard)
+        .unwrap();
+    assert_eq!(material.base_color, Color::srgba(1., 0., 0., 0.3));
+}
+
+#[derive(Debug, Clone, Asset, Reflect, AsBindGroup)]
+pub struct Glow {
+    #[uniform(0)]
+    pub intensity: f32,
+    #[uniform(1)]
+    pub color: LinearRgba,
+}
+
+impl Material for Glow {}
+
+#[test]
+fn reflect_material() {
+    let mut app = common::app();
+    app.init_resource::<Assets<Glow>>()
+        .register_opacity_material3d_reflect::<Glow>();
+    let handle = app.world_mut().resource_mut::<Assets<Glow>>().add(Glow {
+        intensity: 2.,
+        color: LinearRgba::WHITE,
+    });
+    app.world_mut()
+        .spawn((Opacity(0.5), MeshMaterial3d(handle.clone())));
+    app.update();
+    let glow = app.world().resource::<Assets<Glow>>().get(&handle).unwrap();
+    assert_eq!(glow.intensity, 2.);
+    assert_eq!(glow.color.alpha(), 0.5);
+}
+
+#[test]
+fn reflect_material_base_alpha() {
+    let mut app = common::app();
+    app.init_resource::<Assets<Glow>>()
+        .register_opacity_material3d_reflect::<Glow>();
+    let handle = app.world_mut().resource_mut::<Assets<Glow>>().add(Glow {
+        intensity: 2.,
+        color: LinearRgba::WHITE.with_alpha(0.5),
+    });
+    let entity = app
+        .world_mut()
+        .spawn((Opacity(0.5), MeshMaterial3d(handle.clone())))
+        .id();
+    let alpha = |app: &App| {
+        app.world()
+            .resource::<Assets<Glow>>()
+            .get(&handle)
+            .unwrap()
+            .color
+            .alpha()
+    };
+    app.update();
+    assert_eq!(alpha(&app), 0.25);
+
+    *app.world_mut().get_mut::<Opacity>(entity).unwrap() = Opacity::FULL;
+    app.update();
+    assert_eq!(alpha(&app), 0.5);
+}
+
+#[test]
+fn weak_handle() {
+    let mut app = common::app();
+    let strong = app
+        .world_mut()
+        .resource_mut::<Assets<StandardMaterial>>()
+        .add(StandardMaterial::default());
+    app.world_mut()
+        .spawn((Opacity(0.5), MeshMaterial3d(strong.clone_weak())));
+    app.update();
+    let alpha = |app: &App| {
+        app.world()
+            .resource::<Assets<StandardMaterial>>()
+            .get(&strong)
+            .map(|x| x.base_color.alpha())
+    };
+    assert_eq!(alpha(&app), Some(0.5));
+    // Once the asset is gone the entity is skipped.
+    app.world_mut()
+        .resource_mut::<Assets<StandardMaterial>>()
+        .remove(&strong);
+    app.update();
+    assert_eq!(alpha(&app), None);
+}
+
+#[derive(Debug, Clone, Asset, TypePath, AsBindGroup, Opacity)]
+#[opacity(asset)]
+pub struct Panel {
+    #[uniform(0)]
+    #[opacity]
+    pub color: LinearRgba,
+}
+
+impl UiMaterial for Panel {}
+
+#[test]
+fn ui_material() {
+    let mut app = common::app();
+    app.init_resource::<Assets<Panel>>()
+        .register_opacity_ui_material::<Panel>();
+    let handle = app.world_mut().resource_mut::<Assets<Panel>>().add(Panel {
+        color: LinearRgba::WHITE,
+    });
+    app.world_mut()
+        .spawn((Opacity(0.5), MaterialNode(handle.clone())));
+    app.update();
+    let panel = app
+        .world()
+        .resource::<Assets<Panel>>()
+        .get(&handle)
+        .unwrap();
+    assert_eq!(panel.color.alpha(), 0.5);
+}
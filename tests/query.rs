@@ -0,0 +1,147 @@
+use bevy::{
+    asset::{Asset, Assets, Handle},
+    color::Srgba,
+    ecs::system::SystemParam,
+    prelude::{BuildChildren, Component, Res, ResMut, Resource},
+    reflect::TypePath,
+    render::extract_component::ExtractComponentPlugin,
+};
+use bevy_mod_opacity::{Opacity, OpacityExtension, OpacityQuery, RenderOpacity};
+
+mod common;
+
+#[derive(Debug, TypePath, Asset)]
+pub struct Glow(pub Srgba);
+
+#[derive(Debug, Component)]
+pub struct GlowHandle(pub Handle<Glow>);
+
+#[derive(Debug, Resource)]
+pub struct GlowConfig {
+    pub max: f32,
+}
+
+impl OpacityQuery for &GlowHandle {
+    type Cx = (ResMut<'static, Assets<Glow>>, Res<'static, GlowConfig>);
+
+    fn apply_opacity(
+        this: &mut Self::Item<'_>,
+        (assets, config): &mut <Self::Cx as SystemParam>::Item<'_, '_>,
+        opacity: f32,
+    ) {
+        let Some(glow) = assets.get_mut(&this.0) else {
+            return;
+        };
+        glow.0.alpha = opacity * config.max;
+    }
+}
+
+#[test]
+fn tuple_context() {
+    let mut app = common::app();
+    app.init_resource::<Assets<Glow>>()
+        .insert_resource(GlowConfig { max: 0.5 })
+        .register_opacity::<&GlowHandle>();
+    let handle = app
+        .world_mut()
+        .resource_mut::<Assets<Glow>>()
+        .add(Glow(Srgba::WHITE));
+    app.world_mut()
+        .spawn((Opacity(0.5), GlowHandle(handle.clone())));
+    app.update();
+    let glow = app.world().resource::<Assets<Glow>>().get(&handle).unwrap();
+    assert_eq!(glow.0.alpha, 0.25);
+}
+
+#[derive(Debug, Component)]
+pub struct Emissive {
+    pub base: f32,
+    pub strength: f32,
+}
+
+impl OpacityQuery for &mut Emissive {
+    type Cx = ();
+
+    fn apply_opacity(this: &mut Self::Item<'_>, _: &mut (), opacity: f32) {
+        this.strength = this.base * opacity;
+    }
+}
+
+#[test]
+fn unclamped() {
+    let mut app = common::app();
+    app.register_opacity_component::<Emissive>();
+    let root = app.world_mut().spawn(Opacity(2.0)).id();
+    let child = app
+        .world_mut()
+        .spawn((
+            Opacity(2.0),
+            Emissive {
+                base: 1.5,
+                strength: 0.0,
+            },
+        ))
+        .set_parent(root)
+        .id();
+    app.update();
+    assert_eq!(app.world().get::<Emissive>(child).unwrap().strength, 6.0);
+}
+
+#[test]
+fn tuple_query() {
+    let mut app = common::app();
+    app.init_resource::<Assets<Glow>>()
+        .insert_resource(GlowConfig { max: 1.0 })
+        .register_opacity::<(&GlowHandle, &mut Emissive)>();
+    let handle = app
+        .world_mut()
+        .resource_mut::<Assets<Glow>>()
+        .add(Glow(Srgba::WHITE));
+    let both = app
+        .world_mut()
+        .spawn((
+            Opacity(0.5),
+            GlowHandle(handle.clone()),
+            Emissive {
+                base: 2.0,
+                strength: 0.0,
+            },
+        ))
+        .id();
+    // Entities must match every member of the tuple.
+    let emissive_only = app
+        .world_mut()
+        .spawn((
+            Opacity(0.5),
+            Emissive {
+                base: 2.0,
+                strength: 0.0,
+            },
+        ))
+        .id();
+    app.update();
+    let glow = app.world().resource::<Assets<Glow>>().get(&handle).unwrap();
+    assert_eq!(glow.0.alpha, 0.5);
+    assert_eq!(app.world().get::<Emissive>(both).unwrap().strength, 1.0);
+    assert_eq!(
+        app.world().get::<Emissive>(emissive_only).unwrap().strength,
+        0.0
+    );
+}
+
+#[test]
+fn render_opacity() {
+    let mut app = common::app();
+    app.add_plugins(ExtractComponentPlugin::<RenderOpacity>::default());
+    let root = app.world_mut().spawn(Opacity(0.5)).id();
+    let child = app
+        .world_mut()
+        .spawn((Opacity(0.5), RenderOpacity::default()))
+        .set_parent(root)
+        .id();
+    app.update();
+    assert_eq!(
+        app.world().get::<RenderOpacity>(child),
+        Some(&RenderOpacity(0.25))
+    );
+}
@@ -0,0 +1,62 @@
+use bevy::{
+    color::{Alpha, Color},
+    prelude::With,
+    ui::BackgroundColor,
+};
+use bevy_mod_opacity::{FadeIn, Opacity, ScreenFade, ScreenFadeOverlay};
+
+mod common;
+
+#[test]
+fn screen_fade() {
+    let mut app = common::app();
+    let camera = app.world_mut().spawn(ScreenFade::to_black(1.0)).id();
+    common::step(&mut app, 0.5);
+    assert!(app.world().get::<ScreenFade>(camera).is_none());
+    let mut query = app
+        .world_mut()
+        .query_filtered::<(&Opacity, &BackgroundColor), With<ScreenFadeOverlay>>();
+    let (opacity, color) = query.single(app.world());
+    assert_eq!(*opacity, Opacity(0.5));
+    assert_eq!(color.0.alpha(), 0.5);
+
+    common::step(&mut app, 0.6);
+    let mut query = app
+        .world_mut()
+        .query_filtered::<(&Opacity, Option<&FadeIn>), With<ScreenFadeOverlay>>();
+    let (opacity, fade_in) = query.single(app.world());
+    assert_eq!(*opacity, Opacity::FULL);
+    assert!(fade_in.is_none());
+
+    app.world_mut()
+        .entity_mut(camera)
+        .insert(ScreenFade::from_black(1.0));
+    common::step(&mut app, 0.5);
+    let mut query = app
+        .world_mut()
+        .query_filtered::<&Opacity, With<ScreenFadeOverlay>>();
+    assert_eq!(*query.single(app.world()), Opacity(0.5));
+
+    common::step(&mut app, 0.6);
+    let mut query = app
+        .world_mut()
+        .query_filtered::<&Opacity, With<ScreenFadeOverlay>>();
+    assert!(query.get_single(app.world()).is_err());
+}
+
+#[test]
+fn screen_fade_reuse_color() {
+    let mut app = common::app();
+    let camera = app.world_mut().spawn(ScreenFade::to_black(1.0)).id();
+    common::step(&mut app, 0.5);
+    app.world_mut()
+        .entity_mut(camera)
+        .insert(ScreenFade::to_color(Color::srgba(1., 1., 1., 0.5), 1.0));
+    common::step(&mut app, 1.0);
+    let mut query = app
+        .world_mut()
+        .query_filtered::<(&Opacity, &BackgroundColor), With<ScreenFadeOverlay>>();
+    let (opacity, color) = query.single(app.world());
+    assert_eq!(*opacity, Opacity::FULL);
+    assert_eq!(color.0, Color::srgba(1., 1., 1., 0.5));
+}
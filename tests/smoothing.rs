@@ -0,0 +1,51 @@
+use bevy::{
+    app::App,
+    asset::Assets,
+    color::Alpha,
+    pbr::StandardMaterial,
+    prelude::Sprite,
+    sprite::ColorMaterial,
+    time::{Time, Virtual},
+};
+use bevy_mod_opacity::{Opacity, OpacityMap, OpacityPlugin, OpacitySettings};
+
+#[test]
+fn smoothing() {
+    let mut app = App::new();
+    app.init_resource::<Time<Virtual>>()
+        .init_resource::<Assets<ColorMaterial>>()
+        .init_resource::<Assets<StandardMaterial>>()
+        .insert_resource(OpacitySettings {
+            smoothing: Some(0.5),
+            ..Default::default()
+        })
+        .add_plugins(OpacityPlugin);
+    let entity = app
+        .world_mut()
+        .spawn((Opacity::FULL, Sprite::default()))
+        .id();
+    app.update();
+    assert_eq!(app.world().resource::<OpacityMap>().get(entity), Some(1.0));
+
+    app.world_mut().get_mut::<Opacity>(entity).unwrap().0 = 0.;
+    app.update();
+    assert_eq!(
+        app.world().get::<Sprite>(entity).unwrap().color.alpha(),
+        0.5
+    );
+    app.update();
+    assert_eq!(
+        app.world().get::<Sprite>(entity).unwrap().color.alpha(),
+        0.25
+    );
+
+    // Static opacity is reached exactly.
+    for _ in 0..20 {
+        app.update();
+    }
+    assert_eq!(app.world().resource::<OpacityMap>().get(entity), Some(0.0));
+    assert_eq!(
+        app.world().get::<Sprite>(entity).unwrap().color.alpha(),
+        0.0
+    );
+}
@@ -0,0 +1,35 @@
+use bevy::{
+    prelude::{AppExtStates, State, States},
+    state::app::StatesPlugin,
+};
+use bevy_mod_opacity::{FadeCurve, FadeOut};
+
+mod common;
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Default, States)]
+enum Screen {
+    #[default]
+    Menu,
+    Game,
+}
+
+#[test]
+fn then_set_state() {
+    let mut app = common::app();
+    app.add_plugins(StatesPlugin).init_state::<Screen>();
+    let entity = app
+        .world_mut()
+        .spawn(
+            FadeOut::new(1.0)
+                .then_set_state(Screen::Game)
+                .with_fade_curve(FadeCurve::EaseIn),
+        )
+        .id();
+    common::step(&mut app, 0.5);
+    assert_eq!(*app.world().resource::<State<Screen>>(), Screen::Menu);
+    common::step(&mut app, 0.75);
+    assert!(app.world().get_entity(entity).is_err());
+    // The transition is applied in `StateTransition` of the next frame.
+    app.update();
+    assert_eq!(*app.world().resource::<State<Screen>>(), Screen::Game);
+}
@@ -0,0 +1,55 @@
+use bevy::{
+    app::Last,
+    prelude::{Changed, Events, Query, ResMut, Resource},
+};
+use bevy_mod_opacity::{FadeIn, Opacity, OpacityThresholds, ThresholdCrossed};
+
+mod common;
+
+#[test]
+fn threshold_crossed() {
+    let mut app = common::app();
+    let entity = app
+        .world_mut()
+        .spawn((FadeIn::new(1.0), OpacityThresholds::new([0.5])))
+        .id();
+    let mut crossed = Vec::new();
+    for _ in 0..5 {
+        common::step(&mut app, 0.25);
+        crossed.extend(
+            app.world_mut()
+                .resource_mut::<Events<ThresholdCrossed>>()
+                .drain(),
+        );
+    }
+    assert_eq!(
+        crossed,
+        vec![ThresholdCrossed {
+            entity,
+            threshold: 0.5,
+            rising: true,
+        }]
+    );
+}
+
+#[derive(Debug, Default, Resource)]
+pub struct ThresholdChanges(usize);
+
+#[test]
+fn static_opacity_unchanged() {
+    let mut app = common::app();
+    app.init_resource::<ThresholdChanges>().add_systems(
+        Last,
+        |query: Query<(), Changed<OpacityThresholds>>, mut changes: ResMut<ThresholdChanges>| {
+            changes.0 += query.iter().count();
+        },
+    );
+    app.world_mut()
+        .spawn((Opacity(0.5), OpacityThresholds::new([0.25])));
+    app.update();
+    app.update();
+    let changes = app.world().resource::<ThresholdChanges>().0;
+    app.update();
+    app.update();
+    assert_eq!(app.world().resource::<ThresholdChanges>().0, changes);
+}
@@ -0,0 +1,52 @@
+use bevy::{
+    asset::{Asset, Assets},
+    color::LinearRgba,
+    prelude::BuildChildren,
+    reflect::TypePath,
+    render::render_resource::AsBindGroup,
+    sprite::{Material2d, MeshMaterial2d},
+};
+use bevy_mod_opacity::{Opacity, OpacityAsset, OpacityExtension};
+
+mod common;
+
+/// Stand-in for a tilemap crate's chunk material.
+#[derive(Debug, Clone, TypePath, Asset, AsBindGroup)]
+pub struct ChunkMaterial {
+    #[uniform(0)]
+    pub color: LinearRgba,
+}
+
+impl Material2d for ChunkMaterial {}
+
+impl OpacityAsset for ChunkMaterial {
+    fn apply_opacity(&mut self, opacity: f32) {
+        self.color.alpha = opacity;
+    }
+}
+
+#[test]
+fn tilemap_layer() {
+    let mut app = common::app();
+    app.init_resource::<Assets<ChunkMaterial>>()
+        .register_opacity_material2d::<ChunkMaterial>();
+    let mut chunk = || {
+        app.world_mut()
+            .resource_mut::<Assets<ChunkMaterial>>()
+            .add(ChunkMaterial {
+                color: LinearRgba::WHITE,
+            })
+    };
+    let chunks = [chunk(), chunk()];
+    let layer = app.world_mut().spawn(Opacity(0.5)).id();
+    for handle in &chunks {
+        app.world_mut()
+            .spawn(MeshMaterial2d(handle.clone()))
+            .set_parent(layer);
+    }
+    app.update();
+    let assets = app.world().resource::<Assets<ChunkMaterial>>();
+    for handle in &chunks {
+        assert_eq!(assets.get(handle).unwrap().color.alpha, 0.5);
+    }
+}
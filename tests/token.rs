@@ -0,0 +1,51 @@
+use bevy::{ecs::world::World, tasks::block_on};
+use bevy_mod_opacity::{Opacity, OpacityEntityCommands};
+
+mod common;
+
+#[test]
+fn resolves_on_completion() {
+    let mut app = common::app();
+    let entity = app.world_mut().spawn(Opacity::FULL).id();
+    let token = app.world_mut().commands().entity(entity).fade_out(1.0);
+    app.world_mut().flush();
+    common::step(&mut app, 0.5);
+    assert!(!token.is_done());
+    common::step(&mut app, 0.75);
+    assert!(token.is_done());
+    assert!(app.world().get_entity(entity).is_err());
+    block_on(token);
+}
+
+#[test]
+fn resolves_on_despawn() {
+    let mut app = common::app();
+    let entity = app.world_mut().spawn_empty().id();
+    let token = app.world_mut().commands().entity(entity).fade_in(1.0);
+    app.world_mut().flush();
+    common::step(&mut app, 0.25);
+    assert!(!token.is_done());
+    app.world_mut().despawn(entity);
+    common::step(&mut app, 0.25);
+    assert!(token.is_done());
+}
+
+#[test]
+fn wakes_task() {
+    let mut app = common::app();
+    let entity = app.world_mut().spawn_empty().id();
+    let token = app.world_mut().commands().entity(entity).fade_in(0.5);
+    app.world_mut().flush();
+    let handle = std::thread::spawn(move || block_on(token));
+    common::step(&mut app, 1.0);
+    handle.join().unwrap();
+}
+
+#[test]
+fn without_plugin() {
+    let mut world = World::new();
+    let entity = world.spawn_empty().id();
+    let token = world.commands().entity(entity).fade_in(1.0);
+    world.flush();
+    assert!(!token.is_done());
+}
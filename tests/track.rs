@@ -0,0 +1,129 @@
+use bevy::{
+    app::Last,
+    prelude::{Changed, Query, ResMut, Resource},
+};
+use bevy_mod_opacity::{FadeCurve, Opacity, OpacityTrack, TrackInterpolation};
+
+mod common;
+
+#[test]
+fn sample_linear() {
+    let track = OpacityTrack::new([(0.0, 0.0), (0.4, 1.0), (1.0, 0.5)]);
+    assert_eq!(track.duration(), 1.0);
+    assert_eq!(track.sample(-1.0), 0.0);
+    assert_eq!(track.sample(0.0), 0.0);
+    assert!((track.sample(0.2) - 0.5).abs() < 1e-5);
+    assert_eq!(track.sample(0.4), 1.0);
+    assert!((track.sample(0.7) - 0.75).abs() < 1e-5);
+    assert_eq!(track.sample(1.0), 0.5);
+    assert_eq!(track.sample(2.0), 0.5);
+}
+
+#[test]
+fn sample_step() {
+    let track = OpacityTrack::new([(0.4, 1.0), (0.0, 0.0), (1.0, 0.5)])
+        .with_interpolation(TrackInterpolation::Step);
+    assert_eq!(track.sample(0.0), 0.0);
+    assert_eq!(track.sample(0.2), 0.0);
+    assert_eq!(track.sample(0.4), 1.0);
+    assert_eq!(track.sample(0.9), 1.0);
+    assert_eq!(track.sample(1.0), 0.5);
+}
+
+#[test]
+fn sample_curved() {
+    let track = OpacityTrack::from_to_curved(0.2, 1.0, 2.0, FadeCurve::EaseIn);
+    assert_eq!(track.sample(0.0), 0.2);
+    assert!((track.sample(1.0) - 0.4).abs() < 1e-5);
+    assert_eq!(track.sample(2.0), 1.0);
+    let track = OpacityTrack::from_to_curved(1.0, 0.0, 1.0, FadeCurve::SmoothStep);
+    assert_eq!(track.sample(0.0), 1.0);
+    assert!((track.sample(0.5) - 0.5).abs() < 1e-5);
+    assert_eq!(track.sample(1.0), 0.0);
+}
+
+#[test]
+fn sample_empty() {
+    assert_eq!(OpacityTrack::new([]).sample(0.5), 1.0);
+}
+
+#[test]
+fn from_to() {
+    let mut app = common::app();
+    let entity = app
+        .world_mut()
+        .spawn(OpacityTrack::from_to(0.2, 1.0, 2.0))
+        .id();
+    common::step(&mut app, 0.0);
+    assert_eq!(app.world().get::<Opacity>(entity), Some(&Opacity(0.2)));
+    common::step(&mut app, 1.0);
+    assert!((app.world().get::<Opacity>(entity).unwrap().0 - 0.6).abs() < 1e-5);
+    common::step(&mut app, 1.5);
+    assert_eq!(app.world().get::<Opacity>(entity), Some(&Opacity::FULL));
+    assert!(app.world().get::<OpacityTrack>(entity).is_none());
+}
+
+#[derive(Debug, Default, Resource)]
+pub struct OpacityChanges(usize);
+
+#[test]
+fn hold_unchanged() {
+    let mut app = common::app();
+    app.init_resource::<OpacityChanges>().add_systems(
+        Last,
+        |query: Query<(), Changed<Opacity>>, mut changes: ResMut<OpacityChanges>| {
+            changes.0 += query.iter().count();
+        },
+    );
+    let entity = app
+        .world_mut()
+        .spawn(OpacityTrack::new([(0.0, 0.0), (1.0, 0.5)]))
+        .id();
+    common::step(&mut app, 1.5);
+    assert_eq!(app.world().get::<Opacity>(entity), Some(&Opacity(0.5)));
+    let changes = app.world().resource::<OpacityChanges>().0;
+    common::step(&mut app, 0.5);
+    common::step(&mut app, 0.5);
+    assert_eq!(app.world().resource::<OpacityChanges>().0, changes);
+    assert!(app.world().get::<OpacityTrack>(entity).is_some());
+
+    // A held track does not overwrite other writes.
+    app.world_mut().entity_mut(entity).insert(Opacity(0.2));
+    common::step(&mut app, 0.5);
+    assert_eq!(app.world().get::<Opacity>(entity), Some(&Opacity(0.2)));
+
+    app.world_mut()
+        .get_mut::<OpacityTrack>(entity)
+        .unwrap()
+        .restart();
+    common::step(&mut app, 0.5);
+    assert_eq!(app.world().get::<Opacity>(entity), Some(&Opacity(0.25)));
+    common::step(&mut app, 1.0);
+    assert_eq!(app.world().get::<Opacity>(entity), Some(&Opacity(0.5)));
+}
+
+#[test]
+fn from_to_invalid_time() {
+    for time in [0.0, -1.0, f32::NAN] {
+        let mut app = common::app();
+        let entity = app
+            .world_mut()
+            .spawn(OpacityTrack::from_to(0.2, 0.8, time))
+            .id();
+        common::step(&mut app, 0.0);
+        assert_eq!(app.world().get::<Opacity>(entity), Some(&Opacity(0.8)));
+        assert!(app.world().get::<OpacityTrack>(entity).is_none());
+    }
+}
+
+#[test]
+fn lerp() {
+    assert_eq!(Opacity::lerp(0.2, 0.8, 0.0), 0.2);
+    assert_eq!(Opacity::lerp(0.2, 0.8, 0.5), 0.5);
+    assert_eq!(Opacity::lerp(0.2, 0.8, 1.0), 0.8);
+    assert_eq!(Opacity::lerp(0.2, 0.8, -1.0), 0.2);
+    assert_eq!(Opacity::lerp(0.2, 0.8, 2.0), 0.8);
+    assert_eq!(Opacity::lerp(1.0, 0.0, 0.25), 0.75);
+    assert!((Opacity::lerp_unclamped(0.2, 0.8, 2.0) - 1.4).abs() < 1e-6);
+    assert!((Opacity::lerp_unclamped(0.2, 0.8, -1.0) + 0.4).abs() < 1e-6);
+}
@@ -0,0 +1,73 @@
+use bevy::{app::PostUpdate, color::Alpha, color::Color, pbr::wireframe::WireframeColor};
+use bevy_mod_opacity::{BaseAlpha, FadeOut, OpacityExtension};
+
+mod common;
+
+#[test]
+fn wireframe_color() {
+    let mut app = common::app();
+    app.register_opacity_wireframe();
+    let entity = app
+        .world_mut()
+        .spawn((
+            WireframeColor {
+                color: Color::srgba(0., 1., 0., 0.5),
+            },
+            FadeOut::new(1.0),
+        ))
+        .id();
+    common::step(&mut app, 0.5);
+    let wireframe = app.world().get::<WireframeColor>(entity).unwrap();
+    assert_eq!(wireframe.color.alpha(), 0.25);
+    assert_eq!(
+        app.world().get::<BaseAlpha<WireframeColor>>(entity),
+        Some(&BaseAlpha::new(0.5))
+    );
+}
+
+#[cfg(feature = "gizmos")]
+#[test]
+fn wireframe_with_aabb_gizmo() {
+    use bevy::gizmos::aabb::ShowAabbGizmo;
+
+    let mut app = common::app();
+    app.register_opacity_wireframe().register_opacity_gizmos();
+    let entity = app
+        .world_mut()
+        .spawn((
+            WireframeColor {
+                color: Color::srgba(0., 1., 0., 0.8),
+            },
+            ShowAabbGizmo {
+                color: Some(Color::srgba(1., 0., 0., 0.5)),
+            },
+            FadeOut::new(1.0),
+        ))
+        .id();
+    common::step(&mut app, 0.5);
+    assert_eq!(
+        app.world()
+            .get::<WireframeColor>(entity)
+            .unwrap()
+            .color
+            .alpha(),
+        0.4
+    );
+    assert_eq!(
+        app.world()
+            .get::<ShowAabbGizmo>(entity)
+            .unwrap()
+            .color
+            .map(|x| x.alpha()),
+        Some(0.25)
+    );
+}
+
+#[test]
+fn registered_twice() {
+    let mut app = common::app();
+    app.register_opacity_wireframe();
+    let systems = app.get_schedule(PostUpdate).unwrap().systems_len();
+    app.register_opacity_wireframe();
+    assert_eq!(app.get_schedule(PostUpdate).unwrap().systems_len(), systems);
+}